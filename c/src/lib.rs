@@ -1,7 +1,9 @@
 use cdb64::{Cdb, CdbHash, CdbWriter};
 use libc::{c_char, c_int, c_uchar, size_t};
+use std::cell::{Cell, RefCell};
 use std::ffi::CStr;
 use std::fs::File;
+use std::hash::Hasher;
 use std::path::Path;
 use std::ptr;
 use std::slice;
@@ -13,9 +15,115 @@ pub const CDB_ERROR_NULL_POINTER: c_int = -1;
 pub const CDB_ERROR_IO: c_int = -3;
 pub const CDB_ERROR_OPERATION_FAILED: c_int = -5; // General failure
 
+// --- Last-error channel ---
+// Every failing FFI call records its `cdb64::Error`'s `Display` text here so a
+// caller can get more than a numeric code; the numeric return value is still
+// the thing to branch on, this is purely for diagnostics/logging.
+thread_local! {
+    static LAST_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: impl Into<String>) {
+    LAST_ERROR.with(|cell| *cell.borrow_mut() = Some(message.into()));
+}
+
+/// Copies the most recent error message on this thread into `buf`.
+///
+/// Returns the length of the message in bytes (not counting the trailing
+/// nul), regardless of how much of it fit in `buf` -- call again with a
+/// bigger buffer if the return value is `>= buf_len`, the same convention
+/// as `snprintf`. Returns 0 (and leaves `buf` untouched) if there is no
+/// recorded error yet.
+///
+/// # Safety
+///
+/// If non-null, `buf` must point to a writable buffer of at least `buf_len`
+/// bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cdb_last_error_message(buf: *mut c_char, buf_len: size_t) -> size_t {
+    LAST_ERROR.with(|cell| {
+        let borrowed = cell.borrow();
+        let Some(message) = borrowed.as_ref() else {
+            return 0;
+        };
+        let bytes = message.as_bytes();
+        if buf.is_null() || buf_len == 0 {
+            return bytes.len();
+        }
+        let copy_len = bytes.len().min(buf_len - 1);
+        unsafe {
+            ptr::copy_nonoverlapping(bytes.as_ptr(), buf as *mut u8, copy_len);
+            *buf.add(copy_len) = 0;
+        }
+        bytes.len()
+    })
+}
+
+// --- Callback-backed custom hasher ---
+
+/// Signature of the C hash callback: given the seed passed to
+/// `cdb_writer_create_with_hasher`/`cdb_open_with_hasher` and the key bytes,
+/// returns the 64-bit hash.
+pub type CdbHashFn = unsafe extern "C" fn(u64, *const c_uchar, size_t) -> u64;
+
+thread_local! {
+    // The (seed, callback) registered for the `FnHasher` that the *next*
+    // `H::default()` call inside cdb64 will construct. `CdbWriter`/`Cdb`
+    // require `H: Default` with no arguments, so there is no way to carry
+    // per-instance callback state through the generic parameter directly;
+    // every wrapper function below that operates on a callback-backed
+    // `CdbWriterFile`/`CdbFile` sets this immediately before calling into
+    // cdb64, which is safe as long as one thread isn't interleaving calls
+    // against two different callback-backed handles at once.
+    static HASHER_CONFIG: Cell<Option<(u64, CdbHashFn)>> = const { Cell::new(None) };
+}
+
+/// A `std::hash::Hasher` that delegates to a C function pointer instead of
+/// computing a hash itself, so the custom-hasher capability `cdb64` already
+/// supports generically (see `test_read_write_custom_hasher`) is reachable
+/// from C.
+///
+/// The C callback is single-shot (`fn(seed, bytes, len) -> u64`) rather than
+/// incremental, so `write` just buffers the bytes and `finish` makes the one
+/// call -- `cdb64` only ever calls `write` once per key before `finish`, so
+/// there's nothing lost by not folding bytes in as they arrive.
+#[derive(Clone)]
+struct FnHasher {
+    seed: u64,
+    func: CdbHashFn,
+    buf: Vec<u8>,
+}
+
+impl Default for FnHasher {
+    fn default() -> Self {
+        let (seed, func) = HASHER_CONFIG.with(Cell::get)
+            .expect("FnHasher constructed without a registered callback");
+        FnHasher {
+            seed,
+            func,
+            buf: Vec::new(),
+        }
+    }
+}
+
+impl Hasher for FnHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        unsafe { (self.func)(self.seed, self.buf.as_ptr(), self.buf.len()) }
+    }
+}
+
 // --- Writer Struct Wrapper ---
+enum WriterInner {
+    Default(CdbWriter<File, CdbHash>),
+    Custom(CdbWriter<File, FnHasher>, u64, CdbHashFn),
+}
+
 pub struct CdbWriterFile {
-    writer: Option<CdbWriter<File, CdbHash>>,
+    writer: Option<WriterInner>,
 }
 
 /// # Safety
@@ -35,9 +143,49 @@ pub unsafe extern "C" fn cdb_writer_create(path: *const c_char) -> *mut CdbWrite
 
     match CdbWriter::<File, CdbHash>::create(Path::new(path_str)) {
         Ok(writer) => Box::into_raw(Box::new(CdbWriterFile {
-            writer: Some(writer),
+            writer: Some(WriterInner::Default(writer)),
         })),
-        Err(_) => ptr::null_mut(),
+        Err(e) => {
+            set_last_error(e.to_string());
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Creates a CDB writer that hashes keys via a caller-supplied callback
+/// instead of the built-in `CdbHash`, reaching the generic custom-hasher
+/// support `cdb64` already has from C.
+///
+/// # Safety
+///
+/// Same requirements as `cdb_writer_create` for `path`. `hash_fn` must be a
+/// valid function pointer, callable from any thread that ends up calling
+/// `cdb_writer_put`/`cdb_writer_finalize` on the returned handle, for the
+/// lifetime of that handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cdb_writer_create_with_hasher(
+    path: *const c_char,
+    seed: u64,
+    hash_fn: CdbHashFn,
+) -> *mut CdbWriterFile {
+    if path.is_null() {
+        return ptr::null_mut();
+    }
+    let c_str = unsafe { CStr::from_ptr(path) };
+    let path_str = match c_str.to_str() {
+        Ok(s) => s,
+        Err(_) => return ptr::null_mut(),
+    };
+
+    HASHER_CONFIG.with(|cfg| cfg.set(Some((seed, hash_fn))));
+    match CdbWriter::<File, FnHasher>::create(Path::new(path_str)) {
+        Ok(writer) => Box::into_raw(Box::new(CdbWriterFile {
+            writer: Some(WriterInner::Custom(writer, seed, hash_fn)),
+        })),
+        Err(e) => {
+            set_last_error(e.to_string());
+            ptr::null_mut()
+        }
     }
 }
 
@@ -67,10 +215,19 @@ pub unsafe extern "C" fn cdb_writer_put(
     let key = unsafe { slice::from_raw_parts(key_ptr, key_len) };
     let value = unsafe { slice::from_raw_parts(value_ptr, value_len) };
 
-    match writer.put(key, value) {
+    let result = match writer {
+        WriterInner::Default(writer) => writer.put(key, value),
+        WriterInner::Custom(writer, seed, hash_fn) => {
+            HASHER_CONFIG.with(|cfg| cfg.set(Some((*seed, *hash_fn))));
+            writer.put(key, value)
+        }
+    };
+
+    match result {
         Ok(_) => CDB_SUCCESS,
         Err(e) => {
             eprintln!("Error in cdb_writer_put: {}", e);
+            set_last_error(e.to_string());
             CDB_ERROR_IO
         }
     }
@@ -90,11 +247,18 @@ pub unsafe extern "C" fn cdb_writer_finalize(writer_ptr: *mut CdbWriterFile) ->
     match writer_wrapper.writer.take() {
         // Use take to get ownership and leave None
         Some(mut writer) => {
-            // writer is now owned
-            match writer.finalize() {
+            let result = match &mut writer {
+                WriterInner::Default(w) => w.finalize(),
+                WriterInner::Custom(w, seed, hash_fn) => {
+                    HASHER_CONFIG.with(|cfg| cfg.set(Some((*seed, *hash_fn))));
+                    w.finalize()
+                }
+            };
+            match result {
                 Ok(_) => CDB_SUCCESS,
                 Err(e) => {
                     eprintln!("Error in cdb_writer_finalize: {}", e);
+                    set_last_error(e.to_string());
                     // Put the writer back if finalize failed, though it might be in a bad state
                     writer_wrapper.writer = Some(writer);
                     CDB_ERROR_IO
@@ -117,8 +281,13 @@ pub unsafe extern "C" fn cdb_writer_free(writer_ptr: *mut CdbWriterFile) {
 }
 
 // --- Reader Struct Wrapper ---
+enum ReaderInner {
+    Default(Cdb<File, CdbHash>),
+    Custom(Cdb<File, FnHasher>, u64, CdbHashFn),
+}
+
 pub struct CdbFile {
-    reader: Option<Cdb<File, CdbHash>>,
+    reader: Option<ReaderInner>,
 }
 
 /// # Safety
@@ -139,9 +308,46 @@ pub unsafe extern "C" fn cdb_open(path: *const c_char) -> *mut CdbFile {
 
     match Cdb::<File, CdbHash>::open(Path::new(path_str)) {
         Ok(reader) => Box::into_raw(Box::new(CdbFile {
-            reader: Some(reader),
+            reader: Some(ReaderInner::Default(reader)),
+        })),
+        Err(e) => {
+            set_last_error(e.to_string());
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Opens a CDB file written with `cdb_writer_create_with_hasher`, hashing
+/// lookups with the same callback and seed.
+///
+/// # Safety
+///
+/// Same requirements as `cdb_open` for `path`, and as
+/// `cdb_writer_create_with_hasher` for `hash_fn`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cdb_open_with_hasher(
+    path: *const c_char,
+    seed: u64,
+    hash_fn: CdbHashFn,
+) -> *mut CdbFile {
+    if path.is_null() {
+        return ptr::null_mut();
+    }
+    let c_str = unsafe { CStr::from_ptr(path) };
+    let path_str = match c_str.to_str() {
+        Ok(s) => s,
+        Err(_) => return ptr::null_mut(),
+    };
+
+    HASHER_CONFIG.with(|cfg| cfg.set(Some((seed, hash_fn))));
+    match Cdb::<File, FnHasher>::open(Path::new(path_str)) {
+        Ok(reader) => Box::into_raw(Box::new(CdbFile {
+            reader: Some(ReaderInner::Custom(reader, seed, hash_fn)),
         })),
-        Err(_) => ptr::null_mut(),
+        Err(e) => {
+            set_last_error(e.to_string());
+            ptr::null_mut()
+        }
     }
 }
 
@@ -178,7 +384,15 @@ pub unsafe extern "C" fn cdb_get(
     };
     let key = unsafe { slice::from_raw_parts(key_ptr, key_len) };
 
-    match reader.get(key) {
+    let result = match reader {
+        ReaderInner::Default(reader) => reader.get(key),
+        ReaderInner::Custom(reader, seed, hash_fn) => {
+            HASHER_CONFIG.with(|cfg| cfg.set(Some((*seed, *hash_fn))));
+            reader.get(key)
+        }
+    };
+
+    match result {
         Ok(Some(value_vec)) => {
             let len = value_vec.len();
             let boxed_slice = value_vec.into_boxed_slice();
@@ -197,6 +411,7 @@ pub unsafe extern "C" fn cdb_get(
         }
         Err(e) => {
             eprintln!("Error in cdb_get: {}", e);
+            set_last_error(e.to_string());
             unsafe {
                 (*value_out).ptr = ptr::null();
                 (*value_out).len = 0;
@@ -281,7 +496,7 @@ impl OwnedCdbIterator {
 
     /// Get the next key-value pair
     #[allow(clippy::complexity)]
-    fn next(&mut self) -> Option<Result<(Vec<u8>, Vec<u8>), std::io::Error>> {
+    fn next(&mut self) -> Option<Result<(Vec<u8>, Vec<u8>), cdb64::Error>> {
         self.ensure_iterator();
         if let Some(ref mut iter) = self.current_iterator {
             iter.next()
@@ -312,9 +527,16 @@ pub unsafe extern "C" fn cdb_iterator_new(reader_ptr: *mut CdbFile) -> *mut Owne
     // Take ownership of the CdbFile
     let cdb_file = unsafe { Box::from_raw(reader_ptr) };
 
-    // Extract the Cdb from CdbFile
+    // Extract the Cdb from CdbFile. `OwnedCdbIterator` is hardcoded to
+    // `CdbHash` (sequential iteration never hashes a key, so a callback
+    // hasher brings nothing here), so a handle opened with
+    // `cdb_open_with_hasher` can't be iterated this way.
     let cdb = match cdb_file.reader {
-        Some(cdb) => cdb,
+        Some(ReaderInner::Default(cdb)) => cdb,
+        Some(ReaderInner::Custom(..)) => {
+            set_last_error("cdb_iterator_new does not support a callback-hashed CdbFile");
+            return ptr::null_mut();
+        }
         None => return ptr::null_mut(),
     };
 
@@ -366,7 +588,10 @@ pub unsafe extern "C" fn cdb_iterator_next(
 
             CDB_ITERATOR_HAS_NEXT
         }
-        Some(Err(_)) => CDB_ERROR_IO,
+        Some(Err(e)) => {
+            set_last_error(e.to_string());
+            CDB_ERROR_IO
+        }
         None => {
             // No more entries
             unsafe {
@@ -392,3 +617,146 @@ pub unsafe extern "C" fn cdb_iterator_free(iter_ptr: *mut OwnedCdbIterator) {
         unsafe { drop(Box::from_raw(iter_ptr)) };
     }
 }
+
+// --- get_all (duplicate-key) Iterator Implementation ---
+
+/// Owned iterator over every value stored under one key, for databases with
+/// duplicate keys. Owns the `Cdb` for the same reason `OwnedCdbIterator` does:
+/// so C callers don't have to juggle a separate lifetime for the iterator.
+pub struct OwnedCdbGetAllIterator {
+    cdb: Cdb<File, CdbHash>,
+    key: Vec<u8>,
+    current_iterator: Option<cdb64::GetIter<'static, File, CdbHash>>,
+}
+
+impl OwnedCdbGetAllIterator {
+    fn new(cdb: Cdb<File, CdbHash>, key: Vec<u8>) -> Self {
+        OwnedCdbGetAllIterator {
+            cdb,
+            key,
+            current_iterator: None,
+        }
+    }
+
+    /// Initialize the key's probe-chain iterator (called on first next() call)
+    fn ensure_iterator(&mut self) {
+        if self.current_iterator.is_none() {
+            // SAFETY: same reasoning as `OwnedCdbIterator::ensure_iterator` --
+            // the `Cdb` is owned by this struct and outlives the iterator.
+            let cdb_ref: &'static Cdb<File, CdbHash> = unsafe { std::mem::transmute(&self.cdb) };
+            self.current_iterator = Some(cdb_ref.get_iter(&self.key));
+        }
+    }
+
+    fn next(&mut self) -> Option<Result<Vec<u8>, cdb64::Error>> {
+        self.ensure_iterator();
+        self.current_iterator.as_mut()?.next()
+    }
+}
+
+/// Create an iterator over every value stored under `key`, for enumerating
+/// duplicate-key records without reopening the database.
+///
+/// # Safety
+///
+/// `reader_ptr` must be a valid pointer to a `CdbFile` obtained from `cdb_open`.
+/// `key_ptr` must point to a valid memory block of `key_len` bytes.
+/// The returned iterator must be freed with `cdb_get_all_free`.
+/// After calling this function, `reader_ptr` should not be used directly as ownership
+/// is transferred to the iterator.
+///
+/// # Returns
+///
+/// Returns a pointer to `OwnedCdbGetAllIterator` on success, null on failure.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cdb_get_all(
+    reader_ptr: *mut CdbFile,
+    key_ptr: *const c_uchar,
+    key_len: size_t,
+) -> *mut OwnedCdbGetAllIterator {
+    if reader_ptr.is_null() || key_ptr.is_null() {
+        return ptr::null_mut();
+    }
+
+    // Take ownership of the CdbFile
+    let cdb_file = unsafe { Box::from_raw(reader_ptr) };
+
+    // Same `CdbHash`-only limitation as `cdb_iterator_new`: a hash is needed
+    // here (to find the key's probe chain), but `OwnedCdbGetAllIterator` has
+    // no callback-hasher variant yet.
+    let cdb = match cdb_file.reader {
+        Some(ReaderInner::Default(cdb)) => cdb,
+        Some(ReaderInner::Custom(..)) => {
+            set_last_error("cdb_get_all does not support a callback-hashed CdbFile");
+            return ptr::null_mut();
+        }
+        None => return ptr::null_mut(),
+    };
+    let key = unsafe { slice::from_raw_parts(key_ptr, key_len) }.to_vec();
+
+    Box::into_raw(Box::new(OwnedCdbGetAllIterator::new(cdb, key)))
+}
+
+/// Get the next value for the key passed to `cdb_get_all`
+///
+/// # Safety
+///
+/// `iter_ptr` must be a valid pointer to an `OwnedCdbGetAllIterator` obtained from `cdb_get_all`.
+/// `value_out` must point to a valid `CdbData` struct where the result will be stored.
+/// If the function returns `CDB_ITERATOR_HAS_NEXT` (1), the memory pointed to by
+/// `value_out` must be freed by calling `cdb_free_data`.
+///
+/// # Returns
+///
+/// - `CDB_ITERATOR_HAS_NEXT` (1) if there is a next value
+/// - `CDB_ITERATOR_FINISHED` (0) if the key's probe chain is exhausted
+/// - `CDB_ERROR_NULL_POINTER` (-1) if pointers are null
+/// - `CDB_ERROR_IO` (-3) on I/O error
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cdb_get_all_next(
+    iter_ptr: *mut OwnedCdbGetAllIterator,
+    value_out: *mut CdbData,
+) -> c_int {
+    if iter_ptr.is_null() || value_out.is_null() {
+        return CDB_ERROR_NULL_POINTER;
+    }
+
+    let iterator = unsafe { &mut *iter_ptr };
+
+    match iterator.next() {
+        Some(Ok(value)) => {
+            let len = value.len();
+            let boxed_slice = value.into_boxed_slice();
+            unsafe {
+                (*value_out).ptr = Box::into_raw(boxed_slice) as *const c_uchar;
+                (*value_out).len = len;
+            }
+            CDB_ITERATOR_HAS_NEXT
+        }
+        Some(Err(e)) => {
+            set_last_error(e.to_string());
+            CDB_ERROR_IO
+        }
+        None => {
+            unsafe {
+                (*value_out).ptr = ptr::null();
+                (*value_out).len = 0;
+            }
+            CDB_ITERATOR_FINISHED
+        }
+    }
+}
+
+/// Free a `cdb_get_all` iterator and its associated resources
+///
+/// # Safety
+///
+/// `iter_ptr` must be a valid pointer to an `OwnedCdbGetAllIterator` obtained from
+/// `cdb_get_all` or `ptr::null_mut()`. If it's a valid pointer, it must not be used
+/// after this function is called.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cdb_get_all_free(iter_ptr: *mut OwnedCdbGetAllIterator) {
+    if !iter_ptr.is_null() {
+        unsafe { drop(Box::from_raw(iter_ptr)) };
+    }
+}
@@ -39,6 +39,24 @@ fn cdb_write_benchmark(c: &mut Criterion) {
         })
     });
 
+    group.bench_function("write_temp_file_buffered", |b| {
+        b.iter(|| {
+            let temp_file = NamedTempFile::new().unwrap();
+            let mut writer = CdbWriter::<_, CdbHash>::builder()
+                .with_buffer_size(256 * 1024)
+                .with_capacity(NUM_ENTRIES_FOR_BENCH)
+                .build(File::create(temp_file.path()).unwrap())
+                .unwrap();
+            for (key, value) in data.iter() {
+                writer
+                    .put(std::hint::black_box(key), std::hint::black_box(value))
+                    .unwrap();
+            }
+            writer.finalize().unwrap();
+            // temp_file is dropped and deleted here
+        })
+    });
+
     group.bench_function("write_in_memory", |b| {
         b.iter(|| {
             let mut writer = CdbWriter::<_, CdbHash>::new(Cursor::new(Vec::new())).unwrap();
@@ -0,0 +1,74 @@
+//! Optional Bloom filter for fast rejection of absent keys.
+//!
+//! When enabled on the writer, a filter is built over every key at finalize
+//! time and stored in a dedicated region whose location is recorded in the file
+//! trailer. The reader loads it and consults it before touching any hash table,
+//! so a lookup for a definitely-absent key returns without a single slot read.
+//!
+//! The construction is the classic leveldb-style filter: `k` probe positions
+//! per key are derived from a single 64-bit key hash by double hashing
+//! (`h2 = (h1 >> 17) | (h1 << 47)`, then `pos_i = (h1 + i * h2) % nbits`). A key
+//! is reported "maybe present" only when all `k` bits are set.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Minimum filter size so tiny databases still get a few bytes to work with.
+const MIN_BITS: u64 = 64;
+
+/// Number of probe positions for the given bits-per-key, clamped to the same
+/// `[1, 30]` range leveldb uses.
+fn num_probes(bits_per_key: usize) -> u32 {
+    // k = round(bits_per_key * ln2); ln2 ≈ 0.69. Integer math avoids floats so
+    // this stays usable in `no_std` builds.
+    let k = ((bits_per_key * 69 + 50) / 100) as u32;
+    k.clamp(1, 30)
+}
+
+/// Rounds `nbits` up to a whole number of bytes, never below [`MIN_BITS`].
+fn sized_bits(raw_bits: u64) -> (u64, usize) {
+    let bits = raw_bits.max(MIN_BITS);
+    let bytes = bits.div_ceil(8) as usize;
+    (bytes as u64 * 8, bytes)
+}
+
+/// Builds a filter over the given key hashes.
+///
+/// Returns the bit array together with the `nbits` and `k` parameters that must
+/// be persisted so the reader can reproduce the probe sequence.
+pub(crate) fn build(key_hashes: &[u64], bits_per_key: usize) -> (Vec<u8>, u64, u32) {
+    let raw_bits = (key_hashes.len() as u64).saturating_mul(bits_per_key as u64);
+    let (nbits, nbytes) = sized_bits(raw_bits);
+    let k = num_probes(bits_per_key);
+
+    let mut bits = vec![0u8; nbytes];
+    for &hash in key_hashes {
+        let mut h = hash;
+        let delta = (h >> 17) | (h << 47);
+        for _ in 0..k {
+            let pos = h % nbits;
+            bits[(pos / 8) as usize] |= 1 << (pos % 8);
+            h = h.wrapping_add(delta);
+        }
+    }
+    (bits, nbits, k)
+}
+
+/// Tests whether `hash` might be present according to the filter.
+///
+/// A result of `false` guarantees the key is absent; `true` means "maybe".
+pub(crate) fn may_contain(bits: &[u8], nbits: u64, k: u32, hash: u64) -> bool {
+    if nbits == 0 || bits.is_empty() {
+        return true;
+    }
+    let mut h = hash;
+    let delta = (h >> 17) | (h << 47);
+    for _ in 0..k {
+        let pos = h % nbits;
+        if bits[(pos / 8) as usize] & (1 << (pos % 8)) == 0 {
+            return false;
+        }
+        h = h.wrapping_add(delta);
+    }
+    true
+}
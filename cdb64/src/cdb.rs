@@ -1,14 +1,23 @@
+use alloc::vec::Vec;
+use core::hash::Hasher;
+use core::marker::PhantomData;
+
+#[cfg(feature = "std")]
 use std::fs::File;
-use std::hash::Hasher;
-use std::io::{self, ErrorKind};
-use std::marker::PhantomData;
+#[cfg(feature = "std")]
 use std::path::Path;
 
 #[cfg(feature = "mmap")]
 use memmap2::Mmap;
 
+use crate::Error;
+use crate::compress::COMPRESSED_FLAG;
+use crate::control;
 use crate::util::{ReaderAt, read_tuple};
 
+/// Result alias used by the reader, carrying the crate [`Error`].
+type Result<T> = core::result::Result<T, Error>;
+
 /// The size of the CDB header in bytes.
 ///
 /// The header consists of 256 entries, each specifying the offset and length
@@ -60,19 +69,138 @@ pub(crate) struct TableEntry {
 pub struct Cdb<R, H> {
     pub(crate) reader: R,
     pub(crate) header: [TableEntry; 256],
+    pub(crate) codec: crate::compress::Codec,
+    /// Bloom filter loaded from the trailer region, if the file carries one.
+    bloom: Option<BloomRegion>,
+    /// `true` when the hash tables use the SwissTable-style control-byte
+    /// layout, discovered from the trailer.
+    swiss_table: bool,
     _hasher: PhantomData<H>,
     #[cfg(feature = "mmap")]
     mmap: Option<Mmap>,
 }
 
+/// A Bloom filter loaded into memory from the file's filter region.
+struct BloomRegion {
+    bits: Vec<u8>,
+    nbits: u64,
+    k: u32,
+}
+
+/// Peels the optional [format stamp trailer](crate::format) off the tail of
+/// the file, validating its version, and returns the length to use for all
+/// subsequent trailer lookups (the whole file if no stamp is present).
+///
+/// The format stamp stacks outermost of the optional trailers, since it's
+/// the last thing [`CdbWriter::finalize`](crate::CdbWriter::finalize) writes,
+/// so every other trailer scan (checksum, compression/layout) needs to treat
+/// this adjusted length as its "end of file" rather than the real one.
+#[cfg(feature = "std")]
+pub(crate) fn check_format_stamp<R: ReaderAt>(reader: &R, file_len: u64) -> Result<u64> {
+    if file_len < crate::format::FORMAT_TRAILER_LEN as u64 {
+        return Ok(file_len);
+    }
+
+    let mut tail = [0u8; crate::format::FORMAT_TRAILER_LEN];
+    reader.read_exact_at(&mut tail, file_len - crate::format::FORMAT_TRAILER_LEN as u64)?;
+
+    let Some(stamp) = crate::format::parse_format_trailer(&tail)? else {
+        return Ok(file_len);
+    };
+
+    if stamp.version > crate::format::FORMAT_VERSION {
+        return Err(Error::UnsupportedFormat(stamp.version));
+    }
+
+    Ok(file_len - crate::format::FORMAT_TRAILER_LEN as u64)
+}
+
+#[cfg(all(feature = "std", unix))]
+impl<H: Hasher + Default> Cdb<crate::split::SplitReaderAt, H> {
+    /// Opens a multi-volume database written by
+    /// [`CdbWriter::create_split`](crate::CdbWriter::create_split), whose
+    /// `<prefix>.000`, `<prefix>.001`, ... parts this stitches into one
+    /// continuous [`ReaderAt`] via [`SplitReaderAt`](crate::split::SplitReaderAt).
+    ///
+    /// `max_bytes_per_file` must match the value the parts were created
+    /// with — it isn't itself recorded anywhere in the database.
+    pub fn open_split(prefix: impl AsRef<Path>, max_bytes_per_file: u64) -> Result<Self> {
+        let reader = crate::split::SplitReaderAt::open(prefix, max_bytes_per_file)?;
+        let total_len = reader.len()?;
+        let trailer_search_len = check_format_stamp(&reader, total_len)?;
+
+        let mut cdb = Self::new(reader)?;
+        cdb.read_trailer(trailer_search_len)?;
+        Ok(cdb)
+    }
+}
+
+#[cfg(feature = "std")]
 impl<H: Hasher + Default> Cdb<File, H> {
     /// Opens an existing CDB database from a file at the given path.
     ///
     /// This method initializes a `Cdb` instance with a `std::fs::File` as the reader
     /// and uses the specified `Hasher` (defaults to `CdbHash`).
-    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = File::open(path)?;
+        let file_len = file.metadata()?.len();
+        let trailer_search_len = check_format_stamp(&file, file_len)?;
+        let trailer_search_len = crate::checksum::peel_checksum_trailer(&file, trailer_search_len)?;
+        let mut cdb = Self::new(file)?;
+        cdb.read_trailer(trailer_search_len)?;
+        Ok(cdb)
+    }
+
+    /// Opens a CDB database written with [`CdbWriter::finalize_with_checksum`](crate::CdbWriter::finalize_with_checksum),
+    /// recomputing its checksum and returning [`Error::ChecksumMismatch`] if
+    /// it disagrees.
+    ///
+    /// Behaves exactly like [`open`](Self::open) for a file that has no
+    /// checksum trailer — the header and hot `get` path never touch this
+    /// check, so only the presence of the trailer decides whether it runs.
+    pub fn open_verified<P: AsRef<Path>>(path: P) -> Result<Self> {
         let file = File::open(path)?;
-        Self::new(file)
+        let file_len = file.metadata()?.len();
+
+        // Peel the format stamp trailer (if any) first, since it stacks
+        // outermost of the three optional trailers; everything below scopes
+        // its lookups to what's left after that peel.
+        let mut trailer_search_len = check_format_stamp(&file, file_len)?;
+
+        // If a checksum trailer is present, the compression/layout trailer
+        // `read_trailer` looks for sits right before it, not at EOF, so scope
+        // that lookup to the verified body rather than the whole file.
+
+        if trailer_search_len >= HEADER_SIZE + crate::checksum::CHECKSUM_TRAILER_LEN as u64 {
+            let mut tail = [0u8; crate::checksum::CHECKSUM_TRAILER_LEN];
+            file.read_exact_at(
+                &mut tail,
+                trailer_search_len - crate::checksum::CHECKSUM_TRAILER_LEN as u64,
+            )?;
+            if tail[..8] == crate::checksum::CHECKSUM_TRAILER_MAGIC {
+                let expected = u64::from_le_bytes(tail[8..16].try_into().map_err(|_| {
+                    Error::InvalidData("Failed to slice checksum from checksum trailer")
+                })?);
+                let body_len = u64::from_le_bytes(tail[16..24].try_into().map_err(|_| {
+                    Error::InvalidData("Failed to slice body length from checksum trailer")
+                })?);
+                let body_end = HEADER_SIZE
+                    .checked_add(body_len)
+                    .filter(|&end| end <= trailer_search_len)
+                    .ok_or(Error::InvalidData(
+                        "checksum trailer records a body length exceeding the file",
+                    ))?;
+
+                if crate::checksum::checksum_body(&file, body_end)? != expected {
+                    return Err(Error::ChecksumMismatch);
+                }
+                trailer_search_len = body_end;
+            }
+        }
+
+        let mut cdb = Self::new(file)?;
+        cdb.read_trailer(trailer_search_len)?;
+        Ok(cdb)
     }
 
     /// Opens an existing CDB database from a file at the given path using memory-mapped I/O (mmap).
@@ -80,29 +208,128 @@ impl<H: Hasher + Default> Cdb<File, H> {
     /// This method is only available when the `mmap` feature is enabled. It opens the file, creates a memory map,
     /// and reads the CDB header using the mapped memory for efficient access. The returned `Cdb` instance keeps both
     /// the file and the mmap alive for the lifetime of the object. If the header cannot be read, an error is returned.
+    ///
+    /// Equivalent to [`open_mmap_with`](Self::open_mmap_with) with [`MmapOptions::default()`], i.e. no
+    /// `madvise` hints are issued.
+    ///
+    /// Zero-copy lookups against the mapping are [`get_ref`](Self::get_ref)'s
+    /// job, not this constructor's -- `open_mmap` just gets the file mapped so
+    /// `get_ref` (and [`iter_ref`](Self::iter_ref)) have something to borrow
+    /// from for the lifetime of this `Cdb`. The file must not be truncated
+    /// while mapped; see [`get_ref`](Self::get_ref)'s doc comment for the rest
+    /// of that contract.
     #[cfg(feature = "mmap")]
-    pub fn open_mmap<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+    pub fn open_mmap<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::open_mmap_with(path, MmapOptions::default())
+    }
+
+    /// Opens an existing CDB database using mmap, applying `madvise` hints about
+    /// the expected access pattern.
+    ///
+    /// CDB's hash-table probing touches 16-byte slots scattered across the file,
+    /// which defeats the kernel's sequential readahead; [`AdvisePattern::Random`]
+    /// tells it to stop prefetching pages that won't be used. Setting
+    /// `prefault_header` additionally faults in the 4096-byte header (and the
+    /// Bloom filter region, if the file has one) with `MADV_WILLNEED` at open
+    /// time, trading a little extra open latency for fewer page faults on the
+    /// first lookups.
+    #[cfg(feature = "mmap")]
+    pub fn open_mmap_with<P: AsRef<Path>>(path: P, options: MmapOptions) -> Result<Self> {
         let file = File::open(path)?;
         let mmap = unsafe { Mmap::map(&file)? };
+
+        if let AdvisePattern::Random = options.advise {
+            mmap.advise(memmap2::Advice::Random)?;
+        }
+        if options.prefault_header {
+            mmap.advise_range(memmap2::Advice::WillNeed, 0, HEADER_SIZE as usize)?;
+        }
+
         let mut cdb = Cdb {
             reader: file, // Keep the file for ReaderAt, though mmap will be preferred
             header: [TableEntry::default(); 256],
+            codec: crate::compress::Codec::Stored,
+            bloom: None,
+            swiss_table: false,
             _hasher: PhantomData,
             mmap: Some(mmap),
         };
         cdb.read_header_from_mmap()?; // Read header using mmap
+
+        // Discover the codec, table layout, and Bloom filter from the
+        // trailer, if present.
+        let trailer = {
+            let m = cdb.mmap.as_ref().expect("mmap set above");
+            // Peel the format stamp and checksum trailers stacked after it
+            // first, the same way `open` does.
+            let search_len = check_format_stamp(&cdb.reader, m.len() as u64)?;
+            let search_len =
+                crate::checksum::peel_checksum_trailer(&cdb.reader, search_len)? as usize;
+            let tlen = crate::compress::TRAILER_LEN;
+            if search_len >= tlen {
+                crate::compress::parse_trailer(&m[search_len - tlen..search_len])?
+            } else {
+                None
+            }
+        };
+        if let Some(trailer) = trailer {
+            cdb.codec = trailer.codec;
+            cdb.swiss_table = trailer.swiss_table;
+            if trailer.bloom_nbits > 0 {
+                let m = cdb.mmap.as_ref().expect("mmap set above");
+                let start = trailer.bloom_offset as usize;
+                let end = start + trailer.bloom_nbits.div_ceil(8) as usize;
+                if end > m.len() {
+                    return Err(Error::InvalidData("Bloom region exceeds mmap bounds"));
+                }
+                if options.prefault_header {
+                    m.advise_range(memmap2::Advice::WillNeed, start, end - start)?;
+                }
+                cdb.bloom = Some(BloomRegion {
+                    bits: m[start..end].to_vec(),
+                    nbits: trailer.bloom_nbits,
+                    k: trailer.bloom_k,
+                });
+            }
+        }
+
         Ok(cdb)
     }
 }
 
+/// Access-pattern hint for [`Cdb::open_mmap_with`], passed to `madvise`.
+#[cfg(feature = "mmap")]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum AdvisePattern {
+    /// No hint; behaves like [`Cdb::open_mmap`].
+    #[default]
+    Normal,
+    /// `MADV_RANDOM`: disable readahead, matching CDB's scattered probing.
+    Random,
+}
+
+/// Options for [`Cdb::open_mmap_with`].
+#[cfg(feature = "mmap")]
+#[derive(Debug, Copy, Clone, Default)]
+pub struct MmapOptions {
+    /// Access-pattern hint applied to the whole mapping.
+    pub advise: AdvisePattern,
+    /// Eagerly fault in the header and Bloom filter region (if present) with
+    /// `MADV_WILLNEED` at open time.
+    pub prefault_header: bool,
+}
+
 impl<R: ReaderAt, H: Hasher + Default> Cdb<R, H> {
     /// Creates a new CDB instance using the provided `ReaderAt` and a default hasher.
     ///
     /// The hasher defaults to `H::default()`.
-    pub fn new(reader: R) -> io::Result<Self> {
+    pub fn new(reader: R) -> Result<Self> {
         let mut cdb = Cdb {
             reader,
             header: [TableEntry::default(); 256],
+            codec: crate::compress::Codec::Stored,
+            bloom: None,
+            swiss_table: false,
             _hasher: PhantomData,
             #[cfg(feature = "mmap")]
             mmap: None, // mmap is not applicable for generic ReaderAt
@@ -111,8 +338,64 @@ impl<R: ReaderAt, H: Hasher + Default> Cdb<R, H> {
         Ok(cdb)
     }
 
+    /// Overrides the codec used to decode compressed records.
+    ///
+    /// For file-backed databases the codec is discovered automatically from the
+    /// trailer written by [`CdbWriter::with_compression`](crate::CdbWriter::with_compression).
+    /// This method is for readers built over a generic [`ReaderAt`] whose length
+    /// is unknown, so the trailer cannot be located. It must match the codec the
+    /// database was written with. The default is
+    /// [`Codec::Stored`](crate::Codec::Stored).
+    pub fn with_codec(mut self, codec: crate::compress::Codec) -> Self {
+        self.codec = codec;
+        self
+    }
+
+    /// Reads the trailer from the tail of a file of known length, loading the
+    /// codec, table layout, and any Bloom filter region.
+    ///
+    /// A file without a trailer (a plain, filter-less, uncompressed database)
+    /// leaves the reader in its default state.
+    fn read_trailer(&mut self, file_len: u64) -> Result<()> {
+        let tlen = crate::compress::TRAILER_LEN as u64;
+        if file_len < HEADER_SIZE + tlen {
+            return Ok(());
+        }
+        let mut buf = [0u8; crate::compress::TRAILER_LEN];
+        self.reader.read_exact_at(&mut buf, file_len - tlen)?;
+        let Some(trailer) = crate::compress::parse_trailer(&buf)? else {
+            return Ok(());
+        };
+        self.codec = trailer.codec;
+        self.swiss_table = trailer.swiss_table;
+        if trailer.bloom_nbits > 0 {
+            let nbytes = trailer.bloom_nbits.div_ceil(8) as usize;
+            let mut bits = vec![0u8; nbytes];
+            self.reader.read_exact_at(&mut bits, trailer.bloom_offset)?;
+            self.bloom = Some(BloomRegion {
+                bits,
+                nbits: trailer.bloom_nbits,
+                k: trailer.bloom_k,
+            });
+        }
+        Ok(())
+    }
+
+    /// Decodes a raw stored value given its value-length field.
+    ///
+    /// When the field's high [`COMPRESSED_FLAG`](crate::compress::COMPRESSED_FLAG)
+    /// bit is set the payload is run through the database codec; otherwise it is
+    /// returned verbatim.
+    fn decode_value(&self, raw: Vec<u8>, compressed: bool) -> Result<Vec<u8>> {
+        if compressed {
+            self.codec.decompress(&raw)
+        } else {
+            Ok(raw)
+        }
+    }
+
     /// Reads the header from the CDB file into the `Cdb` struct.
-    fn read_header(&mut self) -> io::Result<()> {
+    fn read_header(&mut self) -> Result<()> {
         #[cfg(feature = "mmap")]
         if let Some(mmap_ref) = self.mmap.as_ref() {
             self.header = Self::read_header_from_mmap_internal(mmap_ref)?;
@@ -125,13 +408,13 @@ impl<R: ReaderAt, H: Hasher + Default> Cdb<R, H> {
         for i in 0..256 {
             let offset_bytes: [u8; 8] =
                 header_buf[i * 16..i * 16 + 8].try_into().map_err(|_| {
-                    io::Error::new(ErrorKind::InvalidData, "Failed to slice offset from header")
+                    Error::InvalidData("Failed to slice offset from header")
                 })?;
             let length_bytes: [u8; 8] =
                 header_buf[i * 16 + 8..i * 16 + 16]
                     .try_into()
                     .map_err(|_| {
-                        io::Error::new(ErrorKind::InvalidData, "Failed to slice length from header")
+                        Error::InvalidData("Failed to slice length from header")
                     })?;
 
             self.header[i] = TableEntry {
@@ -143,19 +426,19 @@ impl<R: ReaderAt, H: Hasher + Default> Cdb<R, H> {
     }
 
     #[cfg(feature = "mmap")]
-    fn read_header_from_mmap(&mut self) -> io::Result<()> {
+    fn read_header_from_mmap(&mut self) -> Result<()> {
         if let Some(mmap_ref) = self.mmap.as_ref() {
             self.header = Self::read_header_from_mmap_internal(mmap_ref)?;
             Ok(())
         } else {
-            Err(io::Error::other("Mmap not available for reading header"))
+            Err(Error::InvalidData("Mmap not available for reading header"))
         }
     }
 
     #[cfg(feature = "mmap")]
-    fn read_header_from_mmap_internal(mmap_ref: &Mmap) -> io::Result<[TableEntry; 256]> {
+    fn read_header_from_mmap_internal(mmap_ref: &Mmap) -> Result<[TableEntry; 256]> {
         if mmap_ref.len() < HEADER_SIZE as usize {
-            return Err(io::Error::other("Mmap data is smaller than header size"));
+            return Err(Error::InvalidData("Mmap data is smaller than header size"));
         }
         let header_buf = &mmap_ref[0..HEADER_SIZE as usize];
         let mut header = [TableEntry::default(); 256];
@@ -163,19 +446,13 @@ impl<R: ReaderAt, H: Hasher + Default> Cdb<R, H> {
         for i in 0..256 {
             let offset_bytes: [u8; 8] =
                 header_buf[i * 16..i * 16 + 8].try_into().map_err(|_| {
-                    io::Error::new(
-                        ErrorKind::InvalidData,
-                        "Failed to slice offset from mmap header",
-                    )
+                    Error::InvalidData("Failed to slice offset from mmap header")
                 })?;
             let length_bytes: [u8; 8] =
                 header_buf[i * 16 + 8..i * 16 + 16]
                     .try_into()
                     .map_err(|_| {
-                        io::Error::new(
-                            ErrorKind::InvalidData,
-                            "Failed to slice length from mmap header",
-                        )
+                        Error::InvalidData("Failed to slice length from mmap header")
                     })?;
 
             header[i] = TableEntry {
@@ -215,11 +492,19 @@ impl<R: ReaderAt, H: Hasher + Default> Cdb<R, H> {
     ///         3. If the stored key does not match (hash collision), the probing continues.
     ///    4. If `entry_hash` does not match, probing continues to the next slot.
     /// 6. If the entire hash table chain is traversed without finding the key, it returns `Ok(None)`.
-    pub fn get(&self, key: &[u8]) -> io::Result<Option<Vec<u8>>> {
+    pub fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
         let mut hasher = H::default();
         hasher.write(key);
         let hash_val = hasher.finish();
 
+        // A Bloom filter miss proves the key is absent, so we can skip the hash
+        // table probe entirely.
+        if let Some(ref bloom) = self.bloom {
+            if !crate::bloom::may_contain(&bloom.bits, bloom.nbits, bloom.k, hash_val) {
+                return Ok(None);
+            }
+        }
+
         let table_idx = (hash_val & 0xff) as usize;
         let table_entry = self.header[table_idx];
 
@@ -227,6 +512,10 @@ impl<R: ReaderAt, H: Hasher + Default> Cdb<R, H> {
             return Ok(None);
         }
 
+        if self.swiss_table {
+            return self.get_swiss(table_entry, hash_val, key);
+        }
+
         let starting_slot = (hash_val >> 8) % table_entry.length;
 
         for i in 0..table_entry.length {
@@ -240,16 +529,10 @@ impl<R: ReaderAt, H: Hasher + Default> Cdb<R, H> {
                 let mut slot_buffer = [0u8; 16];
                 self.reader.read_exact_at(&mut slot_buffer, slot_offset)?;
                 let h = u64::from_le_bytes(slot_buffer[0..8].try_into().map_err(|_| {
-                    io::Error::new(
-                        ErrorKind::InvalidData,
-                        "Failed to slice entry_hash from slot",
-                    )
+                    Error::InvalidData("Failed to slice entry_hash from slot")
                 })?);
                 let d = u64::from_le_bytes(slot_buffer[8..16].try_into().map_err(|_| {
-                    io::Error::new(
-                        ErrorKind::InvalidData,
-                        "Failed to slice data_offset from slot",
-                    )
+                    Error::InvalidData("Failed to slice data_offset from slot")
                 })?);
                 (h, d)
             };
@@ -259,16 +542,10 @@ impl<R: ReaderAt, H: Hasher + Default> Cdb<R, H> {
                 let mut slot_buffer = [0u8; 16];
                 self.reader.read_exact_at(&mut slot_buffer, slot_offset)?;
                 let h = u64::from_le_bytes(slot_buffer[0..8].try_into().map_err(|_| {
-                    io::Error::new(
-                        ErrorKind::InvalidData,
-                        "Failed to slice entry_hash from slot",
-                    )
+                    Error::InvalidData("Failed to slice entry_hash from slot")
                 })?);
                 let d = u64::from_le_bytes(slot_buffer[8..16].try_into().map_err(|_| {
-                    io::Error::new(
-                        ErrorKind::InvalidData,
-                        "Failed to slice data_offset from slot",
-                    )
+                    Error::InvalidData("Failed to slice data_offset from slot")
                 })?);
                 (h, d)
             };
@@ -287,15 +564,136 @@ impl<R: ReaderAt, H: Hasher + Default> Cdb<R, H> {
         Ok(None)
     }
 
+    /// Probes a SwissTable-style hash table: `table_entry.offset` points at a
+    /// packed control-byte array of `table_entry.length` bytes (one per slot),
+    /// immediately followed by the classic `(hash, offset)` slot array.
+    ///
+    /// Slots are examined a [`control::GROUP`]-sized group at a time. A single
+    /// tag compare (SIMD or SWAR, see [`control::match_tag`]) narrows a group
+    /// down to its candidate lanes before any full-hash or key check, and the
+    /// walk stops as soon as a group contains an empty control byte, since
+    /// `CdbWriter` never leaves a gap before the end of an entry's probe
+    /// chain.
+    fn get_swiss(
+        &self,
+        table_entry: TableEntry,
+        hash_val: u64,
+        key: &[u8],
+    ) -> Result<Option<Vec<u8>>> {
+        let num_slots = table_entry.length;
+        let slots_offset = table_entry.offset + num_slots;
+        let tag = control::tag(hash_val);
+
+        let starting_slot = (hash_val >> 8) % num_slots;
+        let group_start = starting_slot - (starting_slot % control::GROUP as u64);
+        let num_groups = num_slots.div_ceil(control::GROUP as u64);
+
+        for g in 0..num_groups {
+            let group_base = (group_start + g * control::GROUP as u64) % num_slots;
+            let mut group = [control::EMPTY; control::GROUP];
+            self.reader
+                .read_exact_at(&mut group, table_entry.offset + group_base)?;
+
+            let mut candidates = control::match_tag(&group, tag);
+            while candidates != 0 {
+                let lane = candidates.trailing_zeros() as u64;
+                let slot_idx = (group_base + lane) % num_slots;
+                let (entry_hash, data_offset) = read_slot(&self.reader, slots_offset + slot_idx * 16)?;
+
+                if entry_hash == hash_val {
+                    if let Some(value) = self.get_value_at(data_offset, key)? {
+                        return Ok(Some(value));
+                    }
+                }
+
+                candidates &= candidates - 1;
+            }
+
+            if control::match_empty(&group) != 0 {
+                return Ok(None);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Like [`get_value_at`](Self::get_value_at), but returns the value's
+    /// `(offset, length, compressed)` region instead of reading it, for
+    /// [`get_reader`](Self::get_reader)'s lazy, seekable access.
+    #[cfg(feature = "std")]
+    fn value_region_at(
+        &self,
+        data_offset: u64,
+        expected_key: &[u8],
+    ) -> Result<Option<(u64, u64, bool)>> {
+        let (key_len, val_len_raw) = read_tuple(&self.reader, data_offset)?;
+        let compressed = val_len_raw & COMPRESSED_FLAG != 0;
+        let val_len = val_len_raw & !COMPRESSED_FLAG;
+
+        if key_len as usize != expected_key.len() {
+            return Ok(None);
+        }
+
+        if !expected_key.is_empty() {
+            let mut key_buf = vec![0u8; key_len as usize];
+            self.reader.read_exact_at(&mut key_buf, data_offset + 8)?;
+            if key_buf != expected_key {
+                return Ok(None);
+            }
+        }
+
+        let value_offset = data_offset + 8 + key_len as u64;
+        Ok(Some((value_offset, val_len as u64, compressed)))
+    }
+
+    /// Returns a lazily-reading, seekable view over the value stored under
+    /// `key`, instead of allocating the whole value up front the way
+    /// [`get`](Self::get) does.
+    ///
+    /// Useful when values are large and the caller wants to stream them into
+    /// a socket or parser rather than hold the full payload in memory.
+    /// Returns `Ok(None)` if the key isn't present, and `Error::InvalidData`
+    /// if the record was stored with
+    /// [`CdbWriter::with_compression`](crate::CdbWriter::with_compression) —
+    /// streaming decompression isn't implemented, so use [`get`](Self::get)
+    /// for those.
+    #[cfg(feature = "std")]
+    pub fn get_reader(&self, key: &[u8]) -> Result<Option<ValueReader<'_, R>>> {
+        // get_iter already folds the Bloom-filter check into an
+        // already-exhausted iterator on a miss, so no separate check here.
+        let mut iter = self.get_iter(key);
+        loop {
+            let data_offset = match iter.next_candidate()? {
+                Some(data_offset) => data_offset,
+                None => return Ok(None),
+            };
+
+            if let Some((offset, len, compressed)) = self.value_region_at(data_offset, key)? {
+                if compressed {
+                    return Err(Error::InvalidData(
+                        "get_reader cannot stream a compressed value; use get() instead",
+                    ));
+                }
+                return Ok(Some(ValueReader {
+                    reader: &self.reader,
+                    offset,
+                    len,
+                    pos: 0,
+                }));
+            }
+        }
+    }
+
     /// Reads and verifies a key, then returns its associated value.
     /// Returns `Ok(None)` if the key at `data_offset` does not match `expected_key`.
-    fn get_value_at(&self, data_offset: u64, expected_key: &[u8]) -> io::Result<Option<Vec<u8>>> {
+    fn get_value_at(&self, data_offset: u64, expected_key: &[u8]) -> Result<Option<Vec<u8>>> {
         #[cfg(feature = "mmap")]
         if let Some(mmap_ref) = self.mmap.as_ref() {
             return self.get_value_at_mmap(mmap_ref, data_offset, expected_key);
         }
 
-        let (key_len, val_len) = read_tuple(&self.reader, data_offset)?;
+        let (key_len, val_len_raw) = read_tuple(&self.reader, data_offset)?;
+        let compressed = val_len_raw & COMPRESSED_FLAG != 0;
+        let val_len = val_len_raw & !COMPRESSED_FLAG;
 
         if key_len as usize != expected_key.len() {
             return Ok(None);
@@ -307,7 +705,7 @@ impl<R: ReaderAt, H: Hasher + Default> Cdb<R, H> {
                 self.reader.read_exact_at(&mut value_buf, data_offset + 8)?;
             }
 
-            return Ok(Some(value_buf));
+            return Ok(Some(self.decode_value(value_buf, compressed)?));
         }
 
         let mut key_buf = vec![0u8; key_len as usize];
@@ -322,7 +720,7 @@ impl<R: ReaderAt, H: Hasher + Default> Cdb<R, H> {
             self.reader
                 .read_exact_at(&mut value_buf, data_offset + 8 + key_len as u64)?;
         }
-        Ok(Some(value_buf))
+        Ok(Some(self.decode_value(value_buf, compressed)?))
     }
 
     #[cfg(feature = "mmap")]
@@ -331,28 +729,27 @@ impl<R: ReaderAt, H: Hasher + Default> Cdb<R, H> {
         mmap_ref: &Mmap,
         data_offset: u64,
         expected_key: &[u8],
-    ) -> io::Result<Option<Vec<u8>>> {
+    ) -> Result<Option<Vec<u8>>> {
         let len_offset_usize = data_offset as usize;
         if len_offset_usize + 8 > mmap_ref.len() {
-            return Err(io::Error::new(
-                ErrorKind::UnexpectedEof,
-                "Mmap bounds exceeded for key/value lengths",
-            ));
+            return Err(Error::UnexpectedEof);
         }
 
         let key_len_bytes: [u8; 4] = mmap_ref[len_offset_usize..len_offset_usize + 4]
             .try_into()
             .map_err(|_| {
-                io::Error::new(ErrorKind::InvalidData, "Failed to slice key_len from mmap")
+                Error::InvalidData("Failed to slice key_len from mmap")
             })?;
         let val_len_bytes: [u8; 4] = mmap_ref[len_offset_usize + 4..len_offset_usize + 8]
             .try_into()
             .map_err(|_| {
-                io::Error::new(ErrorKind::InvalidData, "Failed to slice val_len from mmap")
+                Error::InvalidData("Failed to slice val_len from mmap")
             })?;
 
         let key_len = u32::from_le_bytes(key_len_bytes);
-        let val_len = u32::from_le_bytes(val_len_bytes);
+        let val_len_raw = u32::from_le_bytes(val_len_bytes);
+        let compressed = val_len_raw & COMPRESSED_FLAG != 0;
+        let val_len = val_len_raw & !COMPRESSED_FLAG;
 
         if key_len as usize != expected_key.len() {
             return Ok(None);
@@ -363,26 +760,20 @@ impl<R: ReaderAt, H: Hasher + Default> Cdb<R, H> {
                 let start = (data_offset + 8) as usize;
                 let end = start + val_len as usize;
                 if end > mmap_ref.len() {
-                    return Err(io::Error::new(
-                        ErrorKind::InvalidData,
-                        "Mmap bounds exceeded for value",
-                    ));
+                    return Err(Error::InvalidData("Mmap bounds exceeded for value"));
                 }
                 mmap_ref[start..end].to_vec()
             } else {
                 Vec::new()
             };
-            return Ok(Some(value_buf));
+            return Ok(Some(self.decode_value(value_buf, compressed)?));
         }
 
         let key_start = (data_offset + 8) as usize;
         let key_end = key_start + key_len as usize;
 
         if key_end > mmap_ref.len() {
-            return Err(io::Error::new(
-                ErrorKind::InvalidData,
-                "Mmap bounds exceeded for key",
-            ));
+            return Err(Error::InvalidData("Mmap bounds exceeded for key"));
         }
         let key_buf_slice = &mmap_ref[key_start..key_end];
 
@@ -394,17 +785,211 @@ impl<R: ReaderAt, H: Hasher + Default> Cdb<R, H> {
             let val_start = key_end;
             let val_end = val_start + val_len as usize;
             if val_end > mmap_ref.len() {
-                return Err(io::Error::new(
-                    ErrorKind::InvalidData,
-                    "Mmap bounds exceeded for value",
-                ));
+                return Err(Error::InvalidData("Mmap bounds exceeded for value"));
             }
             mmap_ref[val_start..val_end].to_vec()
         } else {
             Vec::new()
         };
 
-        Ok(Some(value_buf))
+        Ok(Some(self.decode_value(value_buf, compressed)?))
+    }
+
+    /// Returns the value for `key` as a slice borrowed from the memory map,
+    /// skipping the copy [`get`](Self::get) makes into an owned `Vec<u8>`.
+    /// [`iter_ref`](Self::iter_ref) is the equivalent for a full scan,
+    /// yielding `(&[u8], &[u8])` pairs the same way.
+    ///
+    /// This is tracked via the `mmap: Option<Mmap>` field already on `Cdb`
+    /// rather than an `as_slice()` method added to [`ReaderAt`] — only the
+    /// `open_mmap` construction path can ever produce a mapped slice, so
+    /// there's no generic-`R` case that would need the trait to expose one.
+    ///
+    /// Only available on a [`Cdb`] opened with [`open_mmap`](Self::open_mmap);
+    /// returns [`Error::InvalidData`] otherwise. Also returns
+    /// `Error::InvalidData` for a record stored with
+    /// [`CdbWriter::with_compression`](crate::CdbWriter::with_compression),
+    /// since decompressing still requires an owned buffer — use
+    /// [`get`](Self::get) for those.
+    #[cfg(feature = "mmap")]
+    pub fn get_ref(&self, key: &[u8]) -> Result<Option<&[u8]>> {
+        let Some(mmap_ref) = self.mmap.as_ref() else {
+            return Err(Error::InvalidData(
+                "get_ref requires a Cdb opened with Cdb::open_mmap",
+            ));
+        };
+
+        let mut hasher = H::default();
+        hasher.write(key);
+        let hash_val = hasher.finish();
+
+        if let Some(ref bloom) = self.bloom {
+            if !crate::bloom::may_contain(&bloom.bits, bloom.nbits, bloom.k, hash_val) {
+                return Ok(None);
+            }
+        }
+
+        for data_offset in self.mmap_slot_candidates(mmap_ref, hash_val)? {
+            if let Some(value) = self.value_ref_at(mmap_ref, data_offset, key)? {
+                return Ok(Some(value));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns every value stored under `key` as slices borrowed from the
+    /// memory map, the zero-copy counterpart of [`get_all`](Self::get_all).
+    ///
+    /// Subject to the same restrictions as [`get_ref`](Self::get_ref): the
+    /// database must be mmap-backed, and no stored record may be compressed.
+    #[cfg(feature = "mmap")]
+    pub fn get_all_ref(&self, key: &[u8]) -> Result<Vec<&[u8]>> {
+        let Some(mmap_ref) = self.mmap.as_ref() else {
+            return Err(Error::InvalidData(
+                "get_all_ref requires a Cdb opened with Cdb::open_mmap",
+            ));
+        };
+
+        let mut hasher = H::default();
+        hasher.write(key);
+        let hash_val = hasher.finish();
+
+        if let Some(ref bloom) = self.bloom {
+            if !crate::bloom::may_contain(&bloom.bits, bloom.nbits, bloom.k, hash_val) {
+                return Ok(Vec::new());
+            }
+        }
+
+        let mut values = Vec::new();
+        for data_offset in self.mmap_slot_candidates(mmap_ref, hash_val)? {
+            if let Some(value) = self.value_ref_at(mmap_ref, data_offset, key)? {
+                values.push(value);
+            }
+        }
+        Ok(values)
+    }
+
+    /// Collects the data offsets of every slot in `hash_val`'s collision
+    /// chain whose `entry_hash` matches, reading the table through the
+    /// mmap (classic linear probing or SwissTable groups, matching whichever
+    /// layout the trailer recorded).
+    #[cfg(feature = "mmap")]
+    fn mmap_slot_candidates(&self, mmap_ref: &Mmap, hash_val: u64) -> Result<Vec<u64>> {
+        let table_idx = (hash_val & 0xff) as usize;
+        let table_entry = self.header[table_idx];
+        let num_slots = table_entry.length;
+        if num_slots == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut candidates = Vec::new();
+
+        if self.swiss_table {
+            let slots_offset = table_entry.offset + num_slots;
+            let tag = control::tag(hash_val);
+            let starting_slot = (hash_val >> 8) % num_slots;
+            let group_start = starting_slot - (starting_slot % control::GROUP as u64);
+            let num_groups = num_slots.div_ceil(control::GROUP as u64);
+
+            for g in 0..num_groups {
+                let group_base = (group_start + g * control::GROUP as u64) % num_slots;
+                let start = (table_entry.offset + group_base) as usize;
+                let end = start + control::GROUP;
+                if end > mmap_ref.len() {
+                    return Err(Error::InvalidData(
+                        "SwissTable control group exceeds mmap bounds",
+                    ));
+                }
+                let group: [u8; control::GROUP] = mmap_ref[start..end].try_into().map_err(|_| {
+                    Error::InvalidData("Failed to slice control group from mmap")
+                })?;
+
+                let mut mask = control::match_tag(&group, tag);
+                while mask != 0 {
+                    let lane = mask.trailing_zeros() as u64;
+                    mask &= mask - 1;
+                    let slot_idx = (group_base + lane) % num_slots;
+                    let (entry_hash, data_offset) =
+                        read_tuple_from_mmap(mmap_ref, slots_offset + slot_idx * 16)?;
+                    if entry_hash == hash_val {
+                        candidates.push(data_offset);
+                    }
+                }
+
+                if control::match_empty(&group) != 0 {
+                    break;
+                }
+            }
+        } else {
+            let starting_slot = (hash_val >> 8) % num_slots;
+            for i in 0..num_slots {
+                let slot_to_check = (starting_slot + i) % num_slots;
+                let (entry_hash, data_offset) =
+                    read_tuple_from_mmap(mmap_ref, table_entry.offset + slot_to_check * 16)?;
+                if entry_hash == 0 && data_offset == 0 {
+                    break;
+                }
+                if entry_hash == hash_val {
+                    candidates.push(data_offset);
+                }
+            }
+        }
+
+        Ok(candidates)
+    }
+
+    /// Reads and verifies a key at `data_offset`, returning its value as a
+    /// slice borrowed from the mmap. Returns `Ok(None)` on a key mismatch and
+    /// `Err(Error::InvalidData)` for a compressed record, since the zero-copy
+    /// contract can't run it through the codec.
+    #[cfg(feature = "mmap")]
+    fn value_ref_at<'a>(
+        &'a self,
+        mmap_ref: &'a Mmap,
+        data_offset: u64,
+        expected_key: &[u8],
+    ) -> Result<Option<&'a [u8]>> {
+        let len_offset = data_offset as usize;
+        if len_offset + 8 > mmap_ref.len() {
+            return Err(Error::UnexpectedEof);
+        }
+
+        let key_len = u32::from_le_bytes(mmap_ref[len_offset..len_offset + 4].try_into().map_err(
+            |_| Error::InvalidData("Failed to slice key_len from mmap"),
+        )?);
+        let val_len_raw = u32::from_le_bytes(
+            mmap_ref[len_offset + 4..len_offset + 8]
+                .try_into()
+                .map_err(|_| Error::InvalidData("Failed to slice val_len from mmap"))?,
+        );
+        let compressed = val_len_raw & COMPRESSED_FLAG != 0;
+        let val_len = val_len_raw & !COMPRESSED_FLAG;
+
+        if key_len as usize != expected_key.len() {
+            return Ok(None);
+        }
+
+        let key_start = len_offset + 8;
+        let key_end = key_start + key_len as usize;
+        if key_end > mmap_ref.len() {
+            return Err(Error::InvalidData("Mmap bounds exceeded for key"));
+        }
+        if !expected_key.is_empty() && &mmap_ref[key_start..key_end] != expected_key {
+            return Ok(None);
+        }
+
+        if compressed {
+            return Err(Error::InvalidData(
+                "get_ref cannot borrow a zero-copy slice for a compressed value; use get() instead",
+            ));
+        }
+
+        let val_start = key_end;
+        let val_end = val_start + val_len as usize;
+        if val_end > mmap_ref.len() {
+            return Err(Error::InvalidData("Mmap bounds exceeded for value"));
+        }
+        Ok(Some(&mmap_ref[val_start..val_end]))
     }
 
     /// Returns an iterator over all key-value pairs in the database.
@@ -413,32 +998,480 @@ impl<R: ReaderAt, H: Hasher + Default> Cdb<R, H> {
     pub fn iter(&self) -> crate::iterator::CdbIterator<'_, R, H> {
         crate::iterator::CdbIterator::new(self)
     }
+
+    /// Returns every value stored under `key`, in probe order.
+    ///
+    /// `get` stops at the first match; this collects all of them, which is
+    /// the only way to observe every value of a key `put` more than once.
+    /// Equivalent to `get_iter(key).collect::<Result<Vec<_>>>()`.
+    pub fn get_all(&self, key: &[u8]) -> Result<Vec<Vec<u8>>> {
+        self.get_iter(key).collect()
+    }
+
+    /// Returns the `n`th value stored under `key` (0-indexed, in probe
+    /// order), or `None` if `key` has `n` or fewer matches.
+    ///
+    /// Equivalent to `get_iter(key).nth(n)`, but doesn't require collecting
+    /// the whole chain into a `Vec` first when only one entry is wanted.
+    pub fn get_nth(&self, key: &[u8], n: usize) -> Result<Option<Vec<u8>>> {
+        self.get_iter(key).nth(n).transpose()
+    }
+
+    /// Returns the value stored under `key` within column `cf`.
+    ///
+    /// Columns are a transparent namespacing convention, not a second format
+    /// concept: this just forwards to [`get`](Self::get) against `key`
+    /// prefixed with `cf`'s 2-byte id, matching how
+    /// [`CdbWriter::put_in`](crate::writer::CdbWriter::put_in) stored it.
+    pub fn get_in(&self, cf: u16, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        self.get(&crate::util::prefix_key(cf, key))
+    }
+
+    /// Returns an iterator over every key-value pair stored under column
+    /// `cf`, with the 2-byte column prefix stripped back off each key.
+    ///
+    /// Built on top of [`iter`](Self::iter) rather than a dedicated walk, so
+    /// it costs a full pass over the database regardless of how many of its
+    /// keys actually belong to `cf`.
+    pub fn iter_in(&self, cf: u16) -> ColumnIter<'_, R, H> {
+        ColumnIter {
+            inner: self.iter(),
+            prefix: cf.to_le_bytes(),
+        }
+    }
+
+    /// Returns a lazy iterator over every value stored under `key`.
+    ///
+    /// Walks the same linear-probe (or SwissTable group) sequence as `get`,
+    /// but keeps following the collision chain past the first match instead
+    /// of stopping, yielding a value each time the stored key equals `key`.
+    /// The chain ends at the first empty slot, matching how `CdbWriter` lays
+    /// out probe sequences.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cdb64::{CdbWriter, CdbHash};
+    /// use std::io::Cursor;
+    ///
+    /// let mut writer = CdbWriter::<_, CdbHash>::new(Cursor::new(Vec::new())).unwrap();
+    /// writer.put(b"tag", b"one").unwrap();
+    /// writer.put(b"tag", b"two").unwrap();
+    /// writer.finalize().unwrap();
+    ///
+    /// let cdb = cdb64::Cdb::<_, CdbHash>::new(writer.into_inner().unwrap()).unwrap();
+    /// let values: Vec<_> = cdb.get_iter(b"tag").collect::<Result<_, _>>().unwrap();
+    /// assert_eq!(values, vec![b"one".to_vec(), b"two".to_vec()]);
+    /// ```
+    pub fn get_iter<'a>(&'a self, key: &[u8]) -> GetIter<'a, R, H> {
+        let mut hasher = H::default();
+        hasher.write(key);
+        let hash_val = hasher.finish();
+
+        // A Bloom filter miss proves the key is absent; hand back an
+        // already-exhausted iterator rather than probing the hash table.
+        let bloom_miss = self
+            .bloom
+            .as_ref()
+            .is_some_and(|bloom| !crate::bloom::may_contain(&bloom.bits, bloom.nbits, bloom.k, hash_val));
+
+        let table_idx = (hash_val & 0xff) as usize;
+        let table_entry = self.header[table_idx];
+        let num_slots = if bloom_miss { 0 } else { table_entry.length };
+
+        let starting_slot = if num_slots > 0 {
+            (hash_val >> 8) % num_slots
+        } else {
+            0
+        };
+        let group_start = starting_slot - (starting_slot % control::GROUP as u64);
+        let num_groups = num_slots.div_ceil(control::GROUP as u64);
+
+        GetIter {
+            cdb: self,
+            key: key.to_vec(),
+            hash_val,
+            table_entry,
+            probe_idx: 0,
+            starting_slot,
+            swiss_group: 0,
+            swiss_group_start: group_start,
+            swiss_num_groups: num_groups,
+            swiss_group_base: 0,
+            swiss_candidates: 0,
+            swiss_group_had_empty: false,
+            done: num_slots == 0,
+        }
+    }
+
+    /// Writes every record to `writer` in the classic `cdbmake` text format.
+    ///
+    /// Each record is emitted as `+klen,dlen:key->data\n`, and the dump is
+    /// terminated by a blank line, matching the format consumed by
+    /// [`CdbWriter::load_text`](crate::CdbWriter::load_text). This provides a
+    /// stable, greppable export that can be diffed or piped back in.
+    #[cfg(feature = "std")]
+    pub fn dump_text<Wr: std::io::Write>(&self, writer: &mut Wr) -> Result<()> {
+        use std::io::Write;
+        for entry in self.iter() {
+            let (key, value) = entry?;
+            write!(writer, "+{},{}:", key.len(), value.len())?;
+            writer.write_all(&key)?;
+            writer.write_all(b"->")?;
+            writer.write_all(&value)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.write_all(b"\n")?;
+        Ok(())
+    }
+
+    /// Returns a zero-copy sequential iterator over every key-value pair,
+    /// yielding slices borrowed directly from the memory map instead of the
+    /// owned `Vec<u8>` pairs [`iter`](Self::iter) allocates.
+    ///
+    /// Subject to the same restrictions as [`get_ref`](Self::get_ref): only
+    /// available on a [`Cdb`] opened with [`open_mmap`](Self::open_mmap),
+    /// and the iterator reports `Error::InvalidData` if it reaches a record
+    /// stored with [`CdbWriter::with_compression`](crate::CdbWriter::with_compression),
+    /// since decompressing still requires an owned buffer.
+    #[cfg(feature = "mmap")]
+    pub fn iter_ref(&self) -> Result<MmapIter<'_, H>> {
+        let Some(mmap_ref) = self.mmap.as_ref() else {
+            return Err(Error::InvalidData(
+                "iter_ref requires a Cdb opened with Cdb::open_mmap",
+            ));
+        };
+
+        let mut end_pos = u64::MAX;
+        let mut has_valid_table_offset = false;
+        for table_entry in &self.header {
+            if table_entry.length > 0 && table_entry.offset > 0 && table_entry.offset >= HEADER_SIZE
+            {
+                end_pos = end_pos.min(table_entry.offset);
+                has_valid_table_offset = true;
+            }
+        }
+        if !has_valid_table_offset {
+            end_pos = HEADER_SIZE;
+        }
+
+        Ok(MmapIter {
+            mmap: mmap_ref,
+            current_pos: HEADER_SIZE,
+            end_pos,
+            _hasher: PhantomData,
+        })
+    }
+}
+
+/// Zero-copy sequential iterator over every key-value pair, obtained from
+/// [`Cdb::iter_ref`].
+///
+/// Mirrors [`CdbIterator`](crate::iterator::CdbIterator)'s traversal order,
+/// but borrows each key and value straight out of the mapped file instead
+/// of copying it into a `Vec<u8>`.
+#[cfg(feature = "mmap")]
+pub struct MmapIter<'cdb, H: Hasher + Default = crate::hash::CdbHash> {
+    mmap: &'cdb Mmap,
+    current_pos: u64,
+    end_pos: u64,
+    _hasher: PhantomData<H>,
+}
+
+#[cfg(feature = "mmap")]
+impl<'cdb, H: Hasher + Default> Iterator for MmapIter<'cdb, H> {
+    type Item = Result<(&'cdb [u8], &'cdb [u8])>;
+
+    /// Advances the iterator and returns the next key/value pair as slices
+    /// borrowed from the memory map, or `Err(Error::InvalidData)` at a
+    /// compressed record (see [`Cdb::iter_ref`]).
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.current_pos >= self.end_pos {
+            return None;
+        }
+
+        let len_offset = self.current_pos as usize;
+        if len_offset + 8 > self.mmap.len() {
+            return Some(Err(Error::UnexpectedEof));
+        }
+
+        let key_len = u32::from_le_bytes(
+            match self.mmap[len_offset..len_offset + 4].try_into() {
+                Ok(bytes) => bytes,
+                Err(_) => {
+                    return Some(Err(Error::InvalidData(
+                        "Failed to slice key_len from mmap",
+                    )));
+                }
+            },
+        );
+        let val_len_raw = u32::from_le_bytes(
+            match self.mmap[len_offset + 4..len_offset + 8].try_into() {
+                Ok(bytes) => bytes,
+                Err(_) => {
+                    return Some(Err(Error::InvalidData(
+                        "Failed to slice val_len from mmap",
+                    )));
+                }
+            },
+        );
+        let compressed = val_len_raw & COMPRESSED_FLAG != 0;
+        let val_len = val_len_raw & !COMPRESSED_FLAG;
+
+        let key_start = len_offset + 8;
+        let key_end = key_start + key_len as usize;
+        let val_start = key_end;
+        let val_end = val_start + val_len as usize;
+        if val_end > self.mmap.len() {
+            return Some(Err(Error::InvalidData(
+                "Record extends beyond mmap bounds",
+            )));
+        }
+        if compressed {
+            return Some(Err(Error::InvalidData(
+                "iter_ref cannot borrow a zero-copy slice for a compressed value; use iter() instead",
+            )));
+        }
+
+        self.current_pos = val_end as u64;
+        Some(Ok((&self.mmap[key_start..key_end], &self.mmap[val_start..val_end])))
+    }
+}
+
+/// A lazily-reading, seekable view over a single value's bytes, obtained
+/// from [`Cdb::get_reader`].
+///
+/// Bytes are pulled on demand through the underlying [`ReaderAt`] rather
+/// than materialized up front, and every read is bounds-clamped to the
+/// value's own region of the file — a read never returns bytes of the next
+/// record. Seeking past the end of the value is allowed; reads from there
+/// return 0 bytes.
+#[cfg(feature = "std")]
+pub struct ValueReader<'cdb, R: ReaderAt> {
+    reader: &'cdb R,
+    offset: u64,
+    len: u64,
+    pos: u64,
+}
+
+#[cfg(feature = "std")]
+impl<R: ReaderAt> std::io::Read for ValueReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let remaining = self.len.saturating_sub(self.pos);
+        if remaining == 0 || buf.is_empty() {
+            return Ok(0);
+        }
+
+        let to_read = (buf.len() as u64).min(remaining) as usize;
+        self.reader
+            .read_exact_at(&mut buf[..to_read], self.offset + self.pos)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        self.pos += to_read as u64;
+        Ok(to_read)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: ReaderAt> std::io::Seek for ValueReader<'_, R> {
+    fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+        let new_pos = match pos {
+            std::io::SeekFrom::Start(n) => n as i64,
+            std::io::SeekFrom::End(n) => self.len as i64 + n,
+            std::io::SeekFrom::Current(n) => self.pos as i64 + n,
+        };
+
+        if new_pos < 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "seek to a negative position",
+            ));
+        }
+
+        self.pos = new_pos as u64;
+        Ok(self.pos)
+    }
+}
+
+/// Reads a `(entry_hash, data_offset)` slot pair through a [`ReaderAt`].
+fn read_slot<R: ReaderAt>(reader: &R, offset: u64) -> Result<(u64, u64)> {
+    let mut buf = [0u8; 16];
+    reader.read_exact_at(&mut buf, offset)?;
+    let hash = u64::from_le_bytes(
+        buf[0..8]
+            .try_into()
+            .map_err(|_| Error::InvalidData("Failed to slice entry_hash from slot"))?,
+    );
+    let data_offset = u64::from_le_bytes(
+        buf[8..16]
+            .try_into()
+            .map_err(|_| Error::InvalidData("Failed to slice data_offset from slot"))?,
+    );
+    Ok((hash, data_offset))
+}
+
+/// Lazy iterator over every value stored under a given key, obtained from
+/// [`Cdb::get_iter`].
+///
+/// Borrows the [`Cdb`] immutably for its lifetime and follows the same
+/// collision-chain probe sequence `get` uses (classic linear probing, or
+/// SwissTable control-byte groups when the file was written with
+/// [`CdbWriter::with_swiss_table`](crate::CdbWriter::with_swiss_table)),
+/// yielding a value for every slot whose stored key matches.
+///
+/// This is the "find-next" style, O(chain length) walk of a single slot
+/// chain — the collision-chain probe itself, not a filtered `iter()` scan —
+/// that [`Cdb::get_all`](Cdb::get_all) and [`Cdb::get_iter`](Cdb::get_iter)
+/// hand back to callers who need every value stored under a duplicated key.
+pub struct GetIter<'cdb, R: ReaderAt, H: Hasher + Default = crate::hash::CdbHash> {
+    cdb: &'cdb Cdb<R, H>,
+    key: Vec<u8>,
+    hash_val: u64,
+    table_entry: TableEntry,
+    // Classic linear-probe state.
+    probe_idx: u64,
+    starting_slot: u64,
+    // SwissTable group-probe state.
+    swiss_group: u64,
+    swiss_group_start: u64,
+    swiss_num_groups: u64,
+    swiss_group_base: u64,
+    swiss_candidates: u16,
+    swiss_group_had_empty: bool,
+    done: bool,
+}
+
+impl<'cdb, R: ReaderAt, H: Hasher + Default> GetIter<'cdb, R, H> {
+    /// Returns the data offset of the next slot whose `entry_hash` matches
+    /// this key's hash, or `None` once the collision chain is exhausted.
+    fn next_candidate(&mut self) -> Result<Option<u64>> {
+        if self.done {
+            return Ok(None);
+        }
+        if self.cdb.swiss_table {
+            self.next_swiss_candidate()
+        } else {
+            self.next_classic_candidate()
+        }
+    }
+
+    fn next_classic_candidate(&mut self) -> Result<Option<u64>> {
+        let num_slots = self.table_entry.length;
+        while self.probe_idx < num_slots {
+            let slot_to_check = (self.starting_slot + self.probe_idx) % num_slots;
+            self.probe_idx += 1;
+            let (entry_hash, data_offset) =
+                read_slot(&self.cdb.reader, self.table_entry.offset + slot_to_check * 16)?;
+
+            if entry_hash == 0 && data_offset == 0 {
+                self.done = true;
+                return Ok(None);
+            }
+            if entry_hash == self.hash_val {
+                return Ok(Some(data_offset));
+            }
+        }
+        self.done = true;
+        Ok(None)
+    }
+
+    fn next_swiss_candidate(&mut self) -> Result<Option<u64>> {
+        let num_slots = self.table_entry.length;
+        let slots_offset = self.table_entry.offset + num_slots;
+        let tag = control::tag(self.hash_val);
+
+        loop {
+            if self.swiss_candidates != 0 {
+                let lane = self.swiss_candidates.trailing_zeros() as u64;
+                self.swiss_candidates &= self.swiss_candidates - 1;
+                let slot_idx = (self.swiss_group_base + lane) % num_slots;
+                let (entry_hash, data_offset) = read_slot(&self.cdb.reader, slots_offset + slot_idx * 16)?;
+                if entry_hash == self.hash_val {
+                    return Ok(Some(data_offset));
+                }
+                continue;
+            }
+
+            if self.swiss_group_had_empty || self.swiss_group >= self.swiss_num_groups {
+                self.done = true;
+                return Ok(None);
+            }
+
+            let group_base = (self.swiss_group_start + self.swiss_group * control::GROUP as u64) % num_slots;
+            let mut group = [control::EMPTY; control::GROUP];
+            self.cdb
+                .reader
+                .read_exact_at(&mut group, self.table_entry.offset + group_base)?;
+            self.swiss_candidates = control::match_tag(&group, tag);
+            self.swiss_group_had_empty = control::match_empty(&group) != 0;
+            self.swiss_group_base = group_base;
+            self.swiss_group += 1;
+        }
+    }
+}
+
+impl<'cdb, R: ReaderAt, H: Hasher + Default> Iterator for GetIter<'cdb, R, H> {
+    type Item = Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let data_offset = match self.next_candidate() {
+                Ok(Some(data_offset)) => data_offset,
+                Ok(None) => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            match self.cdb.get_value_at(data_offset, &self.key) {
+                Ok(Some(value)) => return Some(Ok(value)),
+                Ok(None) => continue,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+/// Iterator over one column's key-value pairs, obtained from
+/// [`Cdb::iter_in`].
+///
+/// Wraps the whole-database [`CdbIterator`](crate::CdbIterator), skipping
+/// records whose key doesn't carry the column's 2-byte prefix and stripping
+/// that prefix off the ones that do.
+pub struct ColumnIter<'cdb, R: ReaderAt, H: Hasher + Default = crate::hash::CdbHash> {
+    inner: crate::iterator::CdbIterator<'cdb, R, H>,
+    prefix: [u8; 2],
+}
+
+impl<'cdb, R: ReaderAt, H: Hasher + Default> Iterator for ColumnIter<'cdb, R, H> {
+    type Item = Result<(Vec<u8>, Vec<u8>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for item in self.inner.by_ref() {
+            match item {
+                Ok((key, value)) => {
+                    if let Some(rest) = key.strip_prefix(self.prefix.as_slice()) {
+                        return Some(Ok((rest.to_vec(), value)));
+                    }
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        None
+    }
 }
 
 #[cfg(feature = "mmap")]
-fn read_tuple_from_mmap(mmap: &Mmap, offset: u64) -> io::Result<(u64, u64)> {
+fn read_tuple_from_mmap(mmap: &Mmap, offset: u64) -> Result<(u64, u64)> {
     let start = offset as usize;
     let end = start + 16;
 
     if end > mmap.len() {
-        return Err(io::Error::new(
-            ErrorKind::UnexpectedEof,
-            "Attempted to read beyond mmap bounds for tuple",
-        ));
+        return Err(Error::UnexpectedEof);
     }
 
     let bytes = &mmap[start..end];
     let first = u64::from_le_bytes(bytes[0..8].try_into().map_err(|_| {
-        io::Error::new(
-            ErrorKind::InvalidData,
-            "Failed to slice first u64 from mmap",
-        )
+        Error::InvalidData("Failed to slice first u64 from mmap")
     })?);
     let second = u64::from_le_bytes(bytes[8..16].try_into().map_err(|_| {
-        io::Error::new(
-            ErrorKind::InvalidData,
-            "Failed to slice second u64 from mmap",
-        )
+        Error::InvalidData("Failed to slice second u64 from mmap")
     })?);
 
     Ok((first, second))
@@ -451,6 +1484,7 @@ mod tests {
     use crate::writer::CdbWriter;
     use std::hash::Hasher as StdHasher;
     use std::io::Cursor;
+    use std::io::ErrorKind;
     #[cfg(feature = "mmap")]
     use std::io::Write;
     use tempfile::NamedTempFile;
@@ -515,7 +1549,10 @@ mod tests {
     fn test_cdb_open_non_existent_file() {
         let result = Cdb::<File, CdbHash>::open("non_existent_file.cdb");
         assert!(result.is_err());
-        assert_eq!(result.err().unwrap().kind(), ErrorKind::NotFound);
+        assert!(matches!(
+            result.err().unwrap(),
+            Error::Io(e) if e.kind() == ErrorKind::NotFound
+        ));
     }
 
     #[test]
@@ -592,7 +1629,7 @@ mod tests {
         let cursor = Cursor::new(data.clone());
         let result = Cdb::<_, CdbHash>::new(cursor);
         assert!(result.is_err());
-        assert_eq!(result.err().unwrap().kind(), ErrorKind::UnexpectedEof);
+        assert!(matches!(result.err().unwrap(), Error::UnexpectedEof));
 
         #[cfg(feature = "mmap")]
         {
@@ -604,12 +1641,7 @@ mod tests {
             }
             let result_mmap = Cdb::<File, CdbHash>::open_mmap(path);
             assert!(result_mmap.is_err());
-            let err_kind = result_mmap.err().unwrap().kind();
-            assert!(
-                err_kind == ErrorKind::InvalidData || err_kind == ErrorKind::Other,
-                "Unexpected error kind: {:?}",
-                err_kind
-            );
+            assert!(matches!(result_mmap.err().unwrap(), Error::InvalidData(_)));
         }
     }
 
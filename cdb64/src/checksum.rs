@@ -0,0 +1,100 @@
+//! Optional whole-file integrity checksum for [`CdbWriter::finalize_with_checksum`](crate::CdbWriter::finalize_with_checksum).
+//!
+//! Not cryptographic — just cheap corruption detection over everything after
+//! the 4096-byte header (the data section, the hash tables, and any
+//! compression/Bloom trailer already written). The checksum lives in its own
+//! small trailer appended strictly after those bytes and is detected the same
+//! way as [`crate::compress`]'s trailer: by scanning for a magic marker at
+//! EOF, so files written without it keep opening exactly as before.
+
+use crate::Error;
+use crate::cdb::HEADER_SIZE;
+use crate::util::ReaderAt;
+
+/// Magic marking the checksum trailer at the very end of the file.
+pub(crate) const CHECKSUM_TRAILER_MAGIC: [u8; 8] = *b"CDB64SUM";
+/// Total size of the checksum trailer: magic(8) + checksum(8) + body length(8).
+pub(crate) const CHECKSUM_TRAILER_LEN: usize = 24;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// A running FNV-1a checksum fed one chunk of bytes at a time, mirroring a
+/// tracked writer that accumulates a hash over everything it passes through.
+pub(crate) struct RollingChecksum {
+    state: u64,
+}
+
+impl RollingChecksum {
+    pub(crate) fn new() -> Self {
+        Self {
+            state: FNV_OFFSET_BASIS,
+        }
+    }
+
+    pub(crate) fn write(&mut self, bytes: &[u8]) {
+        let mut state = self.state;
+        for &byte in bytes {
+            state ^= byte as u64;
+            state = state.wrapping_mul(FNV_PRIME);
+        }
+        self.state = state;
+    }
+
+    pub(crate) fn finish(&self) -> u64 {
+        self.state
+    }
+}
+
+/// Feeds every byte from [`HEADER_SIZE`] up to `end` through a
+/// [`RollingChecksum`], reading it back via [`ReaderAt`] rather than a write
+/// cursor — shared by [`CdbWriter::finalize_with_checksum`](crate::CdbWriter::finalize_with_checksum)
+/// (checksumming what it just wrote) and
+/// [`Cdb::open_verified`](crate::Cdb::open_verified) (recomputing it to verify).
+pub(crate) fn checksum_body<R: ReaderAt>(reader: &R, end: u64) -> Result<u64, Error> {
+    let mut hasher = RollingChecksum::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut pos = HEADER_SIZE;
+    while pos < end {
+        let to_read = buf.len().min((end - pos) as usize);
+        reader.read_exact_at(&mut buf[..to_read], pos)?;
+        hasher.write(&buf[..to_read]);
+        pos += to_read as u64;
+    }
+    Ok(hasher.finish())
+}
+
+/// Detects a checksum trailer ending at `trailer_search_len` and returns the
+/// length of the body it covers, or `trailer_search_len` unchanged if no
+/// checksum trailer is present there.
+///
+/// This only locates the trailer; it doesn't recompute or compare the
+/// checksum the way [`Cdb::open_verified`](crate::Cdb::open_verified) does.
+/// [`CdbWriter::from_existing`](crate::CdbWriter::from_existing) uses this to
+/// narrow its search for the compression/layout trailer underneath without
+/// paying for a checksum recompute over what may be a very large file just
+/// to resume appending.
+pub(crate) fn peel_checksum_trailer<R: ReaderAt>(
+    reader: &R,
+    trailer_search_len: u64,
+) -> Result<u64, Error> {
+    if trailer_search_len < HEADER_SIZE + CHECKSUM_TRAILER_LEN as u64 {
+        return Ok(trailer_search_len);
+    }
+    let mut tail = [0u8; CHECKSUM_TRAILER_LEN];
+    reader.read_exact_at(&mut tail, trailer_search_len - CHECKSUM_TRAILER_LEN as u64)?;
+    if tail[..8] != CHECKSUM_TRAILER_MAGIC {
+        return Ok(trailer_search_len);
+    }
+    let body_len = u64::from_le_bytes(
+        tail[16..24]
+            .try_into()
+            .map_err(|_| Error::InvalidData("Failed to slice body length from checksum trailer"))?,
+    );
+    HEADER_SIZE
+        .checked_add(body_len)
+        .filter(|&end| end <= trailer_search_len)
+        .ok_or(Error::InvalidData(
+            "checksum trailer records a body length exceeding the file",
+        ))
+}
@@ -0,0 +1,225 @@
+//! A reader for the classic, 32-bit D. J. Bernstein `cdb` format, so
+//! databases produced by the original `cdb` tool (or any library that
+//! matches it) can be opened and migrated even though [`Cdb`](crate::Cdb)
+//! itself reads and writes the 64-bit variant.
+//!
+//! The record layout -- an 8-byte `(key_len: u32, value_len: u32)` header
+//! followed by the key then the value -- is identical to `Cdb`'s, so
+//! [`read_tuple`] is reused unchanged for both the data section and (since a
+//! classic header/slot entry is likewise a pair of `u32`s) the header and
+//! hash-table parsing below. What's genuinely different is the header size
+//! (2048 bytes: 256 `(pos, nslots)` entries instead of `Cdb`'s 4096-byte,
+//! `(u64, u64)` one) and the hash function: classic cdb truncates the djb
+//! recurrence to 32 bits at every step, which [`djb_hash32`] computes
+//! directly in `u32` arithmetic. Since the recurrence only shifts left, adds
+//! and xors, its result equals the low 32 bits of a
+//! [`CdbHash`](crate::CdbHash)'s `u64` result for the same key.
+//!
+//! [`ClassicCdb`] only supports `get` and sequential `iter`ation -- the
+//! classic format has no Bloom filter, compression trailer, or SwissTable
+//! layout for a reader to opt into, so there's nothing else to expose.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::Error;
+use crate::util::{ReaderAt, read_tuple};
+
+/// Result alias used by the reader, carrying the crate [`Error`].
+type Result<T> = core::result::Result<T, Error>;
+
+/// Size of a classic cdb header: 256 table entries, each a `(pos, nslots)`
+/// pair of `u32`s.
+pub const HEADER_SIZE_CLASSIC: u64 = 256 * 4 * 2;
+
+/// The classic djb hash function used by the original `cdb` tool, computed
+/// in native 32-bit arithmetic.
+///
+/// This is the same recurrence as [`CdbHash`](crate::CdbHash)
+/// (`h = ((h << 5) + h) ^ byte`, seeded at 5381), but accumulated in a `u32`
+/// instead of a `u64` -- the classic format's hash table slots only have
+/// room for a 32-bit hash. Shifting left, adding and xoring never carry high
+/// bits down into low ones, so the result equals the low 32 bits of the
+/// `u64` recurrence.
+pub fn djb_hash32(key: &[u8]) -> u32 {
+    let mut hash: u32 = 5381;
+    for &byte in key {
+        hash = (hash << 5).wrapping_add(hash) ^ (byte as u32);
+    }
+    hash
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+struct ClassicTableEntry {
+    pos: u32,
+    nslots: u32,
+}
+
+/// A read-only handle to a classic, 32-bit-format cdb database.
+pub struct ClassicCdb<R: ReaderAt> {
+    reader: R,
+    header: [ClassicTableEntry; 256],
+}
+
+impl<R: ReaderAt> ClassicCdb<R> {
+    /// Parses the 2048-byte header out of `reader`.
+    pub fn new(reader: R) -> Result<Self> {
+        let mut cdb = ClassicCdb {
+            reader,
+            header: [ClassicTableEntry::default(); 256],
+        };
+        cdb.read_header()?;
+        Ok(cdb)
+    }
+
+    fn read_header(&mut self) -> Result<()> {
+        for (i, entry) in self.header.iter_mut().enumerate() {
+            let (pos, nslots) = read_tuple(&self.reader, i as u64 * 8)?;
+            *entry = ClassicTableEntry { pos, nslots };
+        }
+        Ok(())
+    }
+
+    /// Returns the first value stored under `key`, following the classic
+    /// format's linear-probe collision chain (the only probe strategy it
+    /// has -- there is no SwissTable variant to pick between, unlike
+    /// [`Cdb::get`](crate::Cdb::get)).
+    pub fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        let hash = djb_hash32(key);
+        let table_idx = (hash & 0xff) as usize;
+        let entry = self.header[table_idx];
+        if entry.nslots == 0 {
+            return Ok(None);
+        }
+
+        let starting_slot = (hash >> 8) % entry.nslots;
+        for i in 0..entry.nslots {
+            let slot = (starting_slot + i) % entry.nslots;
+            let slot_offset = entry.pos as u64 + slot as u64 * 8;
+            let (slot_hash, data_pos) = read_tuple(&self.reader, slot_offset)?;
+
+            if slot_hash == 0 && data_pos == 0 {
+                return Ok(None);
+            }
+            if slot_hash == hash {
+                if let Some(value) = self.get_value_at(data_pos as u64, key)? {
+                    return Ok(Some(value));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn get_value_at(&self, data_offset: u64, expected_key: &[u8]) -> Result<Option<Vec<u8>>> {
+        let (key_len, val_len) = read_tuple(&self.reader, data_offset)?;
+        if key_len as usize != expected_key.len() {
+            return Ok(None);
+        }
+
+        if !expected_key.is_empty() {
+            let mut key_buf = vec![0u8; key_len as usize];
+            self.reader.read_exact_at(&mut key_buf, data_offset + 8)?;
+            if key_buf != expected_key {
+                return Ok(None);
+            }
+        }
+
+        let mut value_buf = vec![0u8; val_len as usize];
+        if val_len > 0 {
+            self.reader
+                .read_exact_at(&mut value_buf, data_offset + 8 + key_len as u64)?;
+        }
+        Ok(Some(value_buf))
+    }
+
+    /// Returns a sequential iterator over every key-value pair, in the
+    /// order they were written -- including duplicate keys, which `get`
+    /// can't surface past the first match.
+    pub fn iter(&self) -> ClassicIter<'_, R> {
+        ClassicIter::new(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl ClassicCdb<std::fs::File> {
+    /// Opens a classic-format cdb file at `path`.
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let file = std::fs::File::open(path)?;
+        Self::new(file)
+    }
+}
+
+/// Sequential iterator over a [`ClassicCdb`]'s data section, obtained from
+/// [`ClassicCdb::iter`].
+///
+/// Mirrors [`CdbIterator`](crate::CdbIterator)'s walk (stop at the first
+/// hash-table entry seen right after the header), but reads 32-bit table
+/// entries to find that boundary instead of 64-bit ones.
+pub struct ClassicIter<'cdb, R: ReaderAt> {
+    cdb: &'cdb ClassicCdb<R>,
+    current_pos: u64,
+    end_pos: u64,
+}
+
+impl<'cdb, R: ReaderAt> ClassicIter<'cdb, R> {
+    fn new(cdb: &'cdb ClassicCdb<R>) -> Self {
+        let mut end_pos = u32::MAX as u64;
+        let mut has_valid_table_pos = false;
+        for entry in &cdb.header {
+            if entry.nslots > 0 && entry.pos as u64 >= HEADER_SIZE_CLASSIC {
+                end_pos = end_pos.min(entry.pos as u64);
+                has_valid_table_pos = true;
+            }
+        }
+        if !has_valid_table_pos {
+            end_pos = HEADER_SIZE_CLASSIC;
+        }
+        ClassicIter {
+            cdb,
+            current_pos: HEADER_SIZE_CLASSIC,
+            end_pos,
+        }
+    }
+}
+
+impl<'cdb, R: ReaderAt> Iterator for ClassicIter<'cdb, R> {
+    type Item = Result<(Vec<u8>, Vec<u8>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.current_pos >= self.end_pos {
+            return None;
+        }
+
+        let (key_len, val_len) = match read_tuple(&self.cdb.reader, self.current_pos) {
+            Ok(v) => v,
+            Err(e) => {
+                self.current_pos = self.end_pos;
+                return Some(Err(e));
+            }
+        };
+
+        let record_start = self.current_pos + 8;
+        let mut key = vec![0u8; key_len as usize];
+        if key_len > 0 {
+            if let Err(e) = self.cdb.reader.read_exact_at(&mut key, record_start) {
+                self.current_pos = self.end_pos;
+                return Some(Err(e));
+            }
+        }
+
+        let mut value = vec![0u8; val_len as usize];
+        if val_len > 0 {
+            if let Err(e) = self
+                .cdb
+                .reader
+                .read_exact_at(&mut value, record_start + key_len as u64)
+            {
+                self.current_pos = self.end_pos;
+                return Some(Err(e));
+            }
+        }
+
+        self.current_pos = record_start + key_len as u64 + val_len as u64;
+        Some(Ok((key, value)))
+    }
+}
@@ -0,0 +1,195 @@
+//! Optional transparent per-record value compression.
+//!
+//! Compression is a database-level choice recorded once in a small fixed
+//! trailer appended after the hash tables (the 4096-byte header is fully
+//! consumed by the 256 table entries, so there is no room there). Each record
+//! then signals whether its own value is compressed by setting the high bit of
+//! the stored value-length field; values below the writer's threshold, or that
+//! fail to shrink, are stored verbatim with the bit clear.
+//!
+//! The default codec is [`Codec::Stored`], which never sets the flag and writes
+//! no trailer, so databases produced without compression stay byte-for-byte
+//! identical and older readers keep working.
+//!
+//! [`Codec`] is a runtime choice on [`CdbWriter::with_compression`](crate::CdbWriter::with_compression)
+//! rather than a type parameter: the codec has to be known again when the file
+//! is reopened for reading, and persisting its id in the trailer lets `Cdb`
+//! pick it up automatically instead of requiring the caller to match a
+//! generic parameter between the writer and reader ends of the same database.
+
+use alloc::vec::Vec;
+
+use crate::Error;
+
+/// Magic marking the optional trailer at the end of the file.
+pub(crate) const TRAILER_MAGIC: [u8; 8] = *b"CDB64CMP";
+/// Total size of the trailer: magic(8) + codec(1) + table layout(1) +
+/// reserved(2) + bloom offset(8) + bloom nbits(8) + bloom k(4).
+pub(crate) const TRAILER_LEN: usize = 32;
+/// High bit of a stored value-length field: when set, the payload is compressed
+/// with the database's codec.
+pub(crate) const COMPRESSED_FLAG: u32 = 0x8000_0000;
+
+/// Parsed contents of the optional file trailer.
+///
+/// The trailer records both the value codec (see [`Codec`]) and the location of
+/// an optional Bloom filter region. It is written only when the database needs
+/// it — a plain, filter-less, uncompressed file carries no trailer and is
+/// byte-for-byte identical to the classic layout.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Trailer {
+    pub(crate) codec: Codec,
+    /// `true` when the hash tables use the SwissTable-style control-byte
+    /// layout (see [`crate::control`]) instead of the classic bare
+    /// `(hash, offset)` slot array.
+    pub(crate) swiss_table: bool,
+    /// Offset of the Bloom filter bit array, or 0 when there is no filter.
+    pub(crate) bloom_offset: u64,
+    /// Number of bits in the filter (0 when absent).
+    pub(crate) bloom_nbits: u64,
+    /// Probe count used when building the filter.
+    pub(crate) bloom_k: u32,
+}
+
+impl Trailer {
+    /// True when this database needs a trailer written at all.
+    pub(crate) fn is_needed(&self) -> bool {
+        self.codec != Codec::Stored || self.swiss_table || self.bloom_nbits > 0
+    }
+
+    /// Serializes the trailer to its fixed on-disk form.
+    pub(crate) fn to_bytes(self) -> [u8; TRAILER_LEN] {
+        let mut buf = [0u8; TRAILER_LEN];
+        buf[..8].copy_from_slice(&TRAILER_MAGIC);
+        buf[8] = self.codec.id();
+        buf[9] = self.swiss_table as u8;
+        buf[12..20].copy_from_slice(&self.bloom_offset.to_le_bytes());
+        buf[20..28].copy_from_slice(&self.bloom_nbits.to_le_bytes());
+        buf[28..32].copy_from_slice(&self.bloom_k.to_le_bytes());
+        buf
+    }
+}
+
+/// The compression codec applied to record values.
+///
+/// The codec is a file-level option; its id is persisted in the trailer and
+/// validated when the database is opened. A per-record one-byte tag prefix
+/// (raw vs. codec) was considered as an alternative to the trailer-plus-
+/// flag-bit scheme here, but it costs an extra byte on every single record
+/// to encode information that is the same for the whole file, where the
+/// trailer pays that cost exactly once.
+///
+/// [`Zstd`](Codec::Zstd) carries forward the codec chunk0-2 originally
+/// shipped (back when each record framed its value as `[tag][payload]`
+/// instead of using this trailer-plus-flag-bit scheme) so that support isn't
+/// silently lost in the redesign; it's just a variant here now, on the same
+/// footing as `Lz4`/`Snappy`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Codec {
+    /// Values are stored uncompressed. This is the default and produces files
+    /// identical to those written without the compression feature.
+    #[default]
+    Stored,
+
+    /// Values are compressed with LZ4 (block format).
+    #[cfg(feature = "lz4")]
+    Lz4,
+
+    /// Values are compressed with Snappy.
+    #[cfg(feature = "snappy")]
+    Snappy,
+
+    /// Values are compressed with zstd at the writer's configured
+    /// [`codec_level`](crate::CdbWriter::with_compression).
+    #[cfg(feature = "zstd")]
+    Zstd,
+}
+
+impl Codec {
+    /// The id persisted in the file trailer for this codec.
+    pub(crate) fn id(self) -> u8 {
+        match self {
+            Codec::Stored => 0,
+            #[cfg(feature = "lz4")]
+            Codec::Lz4 => 1,
+            #[cfg(feature = "snappy")]
+            Codec::Snappy => 2,
+            #[cfg(feature = "zstd")]
+            Codec::Zstd => 3,
+        }
+    }
+
+    /// Resolves a trailer codec id back into a [`Codec`].
+    ///
+    /// Returns [`Error::InvalidData`] for an id whose codec is not compiled in,
+    /// so a file written with a codec the reader lacks fails loudly rather than
+    /// returning garbage.
+    pub(crate) fn from_id(id: u8) -> Result<Self, Error> {
+        match id {
+            0 => Ok(Codec::Stored),
+            #[cfg(feature = "lz4")]
+            1 => Ok(Codec::Lz4),
+            #[cfg(feature = "snappy")]
+            2 => Ok(Codec::Snappy),
+            #[cfg(feature = "zstd")]
+            3 => Ok(Codec::Zstd),
+            _ => Err(Error::InvalidData("unknown or unsupported value codec")),
+        }
+    }
+
+    /// Compresses a value payload. Never called for [`Codec::Stored`].
+    pub(crate) fn compress(self, value: &[u8], _level: i32) -> Result<Vec<u8>, Error> {
+        match self {
+            Codec::Stored => Ok(value.to_vec()),
+            #[cfg(feature = "lz4")]
+            Codec::Lz4 => Ok(lz4_flex::block::compress_prepend_size(value)),
+            #[cfg(feature = "snappy")]
+            Codec::Snappy => snap::raw::Encoder::new()
+                .compress_vec(value)
+                .map_err(|_| Error::InvalidData("snappy compression failed")),
+            #[cfg(feature = "zstd")]
+            Codec::Zstd => zstd::bulk::compress(value, _level)
+                .map_err(|_| Error::InvalidData("zstd compression failed")),
+        }
+    }
+
+    /// Decompresses a value payload that was stored with the compressed flag.
+    pub(crate) fn decompress(self, payload: &[u8]) -> Result<Vec<u8>, Error> {
+        match self {
+            Codec::Stored => Ok(payload.to_vec()),
+            #[cfg(feature = "lz4")]
+            Codec::Lz4 => lz4_flex::block::decompress_size_prepended(payload)
+                .map_err(|_| Error::InvalidData("lz4 decompression failed")),
+            #[cfg(feature = "snappy")]
+            Codec::Snappy => snap::raw::Decoder::new()
+                .decompress_vec(payload)
+                .map_err(|_| Error::InvalidData("snappy decompression failed")),
+            #[cfg(feature = "zstd")]
+            Codec::Zstd => zstd::stream::decode_all(payload)
+                .map_err(|_| Error::InvalidData("zstd decompression failed")),
+        }
+    }
+}
+
+/// Parses the trailer from a trailing byte window, if it carries the magic.
+///
+/// Returns `Ok(None)` when the bytes are not a trailer (e.g. a file written
+/// before this feature), so readers of trailer-less files behave exactly as
+/// before.
+pub(crate) fn parse_trailer(tail: &[u8]) -> Result<Option<Trailer>, Error> {
+    if tail.len() < TRAILER_LEN || tail[..8] != TRAILER_MAGIC {
+        return Ok(None);
+    }
+    let codec = Codec::from_id(tail[8])?;
+    let swiss_table = tail[9] != 0;
+    let bloom_offset = u64::from_le_bytes(tail[12..20].try_into().unwrap());
+    let bloom_nbits = u64::from_le_bytes(tail[20..28].try_into().unwrap());
+    let bloom_k = u32::from_le_bytes(tail[28..32].try_into().unwrap());
+    Ok(Some(Trailer {
+        codec,
+        swiss_table,
+        bloom_offset,
+        bloom_nbits,
+        bloom_k,
+    }))
+}
@@ -0,0 +1,110 @@
+//! Control-byte groups for the optional SwissTable-style table layout.
+//!
+//! In this layout each hash table is preceded by a packed array of one control
+//! byte per slot: the low 7 bits of the slot's hash with the top bit clear for a
+//! full slot, and [`EMPTY`] (`0xFF`) for an empty one — exactly the hashbrown /
+//! `odht` encoding. A lookup broadcasts the query's 7-bit tag across a 16-byte
+//! group and compares all lanes at once (SSE2 where available, a SWAR fallback
+//! otherwise), so only the matching slots need a full-hash confirmation.
+
+/// Number of slots examined per control-byte group.
+pub(crate) const GROUP: usize = 16;
+
+/// Control byte marking an empty slot.
+pub(crate) const EMPTY: u8 = 0xFF;
+
+/// The control byte (7-bit tag, top bit clear) for a full slot's hash.
+pub(crate) fn tag(hash: u64) -> u8 {
+    (hash & 0x7f) as u8
+}
+
+/// Returns a bitmask of the lanes in `group` whose control byte equals `tag`.
+///
+/// Bit `i` is set when `group[i] == tag`. `group` must be exactly [`GROUP`]
+/// bytes. Uses SSE2 on x86-64 and a portable SWAR fallback elsewhere.
+pub(crate) fn match_tag(group: &[u8; GROUP], tag: u8) -> u16 {
+    #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+    {
+        // SAFETY: SSE2 is statically guaranteed by the cfg above, and `group`
+        // is exactly 16 bytes so the unaligned load stays in bounds.
+        unsafe {
+            use core::arch::x86_64::*;
+            let needle = _mm_set1_epi8(tag as i8);
+            let haystack = _mm_loadu_si128(group.as_ptr() as *const __m128i);
+            let eq = _mm_cmpeq_epi8(haystack, needle);
+            _mm_movemask_epi8(eq) as u16
+        }
+    }
+    #[cfg(not(all(target_arch = "x86_64", target_feature = "sse2")))]
+    {
+        swar_match(group, tag)
+    }
+}
+
+/// Returns a bitmask of the empty lanes in `group`.
+pub(crate) fn match_empty(group: &[u8; GROUP]) -> u16 {
+    match_tag(group, EMPTY)
+}
+
+/// Portable SWAR equivalent of [`match_tag`], also used directly by tests.
+///
+/// For each 8-byte word it XORs in the broadcast tag so matching bytes become
+/// zero, then locates zero bytes with the classic
+/// `(x - 0x01..) & ~x & 0x80..` trick.
+#[cfg_attr(
+    all(target_arch = "x86_64", target_feature = "sse2"),
+    allow(dead_code)
+)]
+pub(crate) fn swar_match(group: &[u8; GROUP], tag: u8) -> u16 {
+    const LO: u64 = 0x0101_0101_0101_0101;
+    const HI: u64 = 0x8080_8080_8080_8080;
+    let broadcast = LO.wrapping_mul(tag as u64);
+
+    let mut mask = 0u16;
+    for word_idx in 0..2 {
+        let start = word_idx * 8;
+        let word = u64::from_le_bytes(group[start..start + 8].try_into().unwrap());
+        let x = word ^ broadcast; // zero bytes mark matches
+        let zeros = x.wrapping_sub(LO) & !x & HI; // high bit set per zero byte
+        // Collapse each byte's high bit into consecutive mask bits.
+        for byte in 0..8 {
+            if zeros & (0x80 << (byte * 8)) != 0 {
+                mask |= 1 << (start + byte);
+            }
+        }
+    }
+    mask
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tag_clears_top_bit() {
+        assert_eq!(tag(0xff), 0x7f);
+        assert_eq!(tag(0x80), 0x00);
+        assert_eq!(tag(0x1234_5600_0000_0042), 0x42);
+    }
+
+    #[test]
+    fn swar_matches_expected_lanes() {
+        let mut group = [EMPTY; GROUP];
+        group[0] = 0x2a;
+        group[7] = 0x2a;
+        group[8] = 0x2a;
+        group[15] = 0x2a;
+        assert_eq!(swar_match(&group, 0x2a), 0b1000_0001_1000_0001);
+    }
+
+    #[test]
+    fn swar_and_simd_agree() {
+        let mut group = [EMPTY; GROUP];
+        for (i, b) in group.iter_mut().enumerate() {
+            *b = (i as u8 * 7) & 0x7f;
+        }
+        group[3] = 0x10;
+        group[11] = 0x10;
+        assert_eq!(match_tag(&group, 0x10), swar_match(&group, 0x10));
+    }
+}
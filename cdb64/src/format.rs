@@ -0,0 +1,75 @@
+//! Optional format/version stamp trailer for [`CdbWriter::with_format_stamp`](crate::CdbWriter::with_format_stamp).
+//!
+//! The classic layout has no magic bytes or version anywhere in the file, so
+//! a truncated or entirely foreign file is silently mis-parsed rather than
+//! rejected, and newer optional features (compression, the checksum trailer)
+//! have no way to announce themselves. This trailer fixes that without
+//! touching the 4096-byte header or the `get` hot path: it is detected the
+//! same way as the other optional trailers, by scanning for a magic marker
+//! at EOF, so a classic file with no stamp opens exactly as before.
+//!
+//! It stacks outermost of the three optional trailers (after the
+//! compression/layout trailer and the checksum trailer, if either is
+//! present), since it is the last thing written by `finalize`.
+
+use crate::Error;
+
+/// Magic marking the format stamp trailer at the very end of the file.
+pub(crate) const FORMAT_TRAILER_MAGIC: [u8; 8] = *b"CDB64FMT";
+/// Total size of the trailer: magic(8) + version(2) + hasher id(2) + flags(4).
+pub(crate) const FORMAT_TRAILER_LEN: usize = 16;
+
+/// The format version this build of the crate writes and understands.
+/// `Cdb::open` rejects a file stamped with a newer version it doesn't know
+/// how to read.
+pub(crate) const FORMAT_VERSION: u16 = 1;
+
+/// Set when the database was written with a codec other than [`crate::Codec::Stored`].
+pub(crate) const FLAG_COMPRESSED: u32 = 1 << 0;
+/// Set when the file carries a [`crate::checksum`] trailer.
+pub(crate) const FLAG_CHECKSUM: u32 = 1 << 1;
+/// Set when the hash tables use the SwissTable-style control-byte layout.
+pub(crate) const FLAG_SWISS_TABLE: u32 = 1 << 2;
+/// Set when the file carries a Bloom filter region.
+pub(crate) const FLAG_BLOOM: u32 = 1 << 3;
+
+/// Parsed contents of the format stamp trailer.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct FormatStamp {
+    pub(crate) version: u16,
+    /// Opaque identifier for the `Hasher` implementation the writer was
+    /// configured with, passed in by the caller of
+    /// [`with_format_stamp`](crate::CdbWriter::with_format_stamp). Informational
+    /// only — `Cdb::open` does not enforce it, since it has no way to ask an
+    /// arbitrary `H: Hasher + Default` for its own id at compile time.
+    #[allow(dead_code)]
+    pub(crate) hasher_id: u16,
+    pub(crate) flags: u32,
+}
+
+impl FormatStamp {
+    pub(crate) fn to_bytes(self) -> [u8; FORMAT_TRAILER_LEN] {
+        let mut buf = [0u8; FORMAT_TRAILER_LEN];
+        buf[..8].copy_from_slice(&FORMAT_TRAILER_MAGIC);
+        buf[8..10].copy_from_slice(&self.version.to_le_bytes());
+        buf[10..12].copy_from_slice(&self.hasher_id.to_le_bytes());
+        buf[12..16].copy_from_slice(&self.flags.to_le_bytes());
+        buf
+    }
+}
+
+/// Parses the format stamp trailer from a trailing byte window, if it
+/// carries the magic. Returns `Ok(None)` for a classic file with no stamp.
+pub(crate) fn parse_format_trailer(tail: &[u8]) -> Result<Option<FormatStamp>, Error> {
+    if tail.len() < FORMAT_TRAILER_LEN || tail[..8] != FORMAT_TRAILER_MAGIC {
+        return Ok(None);
+    }
+    let version = u16::from_le_bytes(tail[8..10].try_into().unwrap());
+    let hasher_id = u16::from_le_bytes(tail[10..12].try_into().unwrap());
+    let flags = u32::from_le_bytes(tail[12..16].try_into().unwrap());
+    Ok(Some(FormatStamp {
+        version,
+        hasher_id,
+        flags,
+    }))
+}
@@ -0,0 +1,75 @@
+//! Minimal, `no_std`-friendly I/O traits used throughout the crate.
+//!
+//! The classic cdb code was written directly against `std::io`. To make the
+//! reader usable on embedded targets we vendor our own tiny `Read`/`Write`/`Seek`
+//! traits here (rather than pulling in an unmaintained `core_io`-style crate) and
+//! route every fallible operation through the crate [`Error`](crate::Error).
+//!
+//! With the `std` feature enabled these traits are implemented for anything that
+//! already implements the corresponding `std::io` trait, so callers keep passing
+//! `File`, `Cursor`, and friends unchanged.
+
+use crate::Error;
+
+/// Result alias for the crate-local I/O traits.
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// Where a [`Seek`] cursor should be moved to.
+///
+/// A deliberately trimmed-down version of `std::io::SeekFrom` that models
+/// absolute positions only; the `std`-only writer seeks through
+/// `std::io::SeekFrom` directly.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SeekFrom {
+    /// Set the cursor to the given byte offset from the start of the stream.
+    Start(u64),
+}
+
+/// A source that can be read sequentially.
+pub trait Read {
+    /// Reads some bytes into `buf`, returning how many were read.
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize>;
+}
+
+/// A sink that accepts bytes.
+pub trait Write {
+    /// Writes the entire buffer, failing if it cannot all be written.
+    fn write_all(&mut self, buf: &[u8]) -> Result<()>;
+
+    /// Flushes any buffered bytes to the underlying sink.
+    fn flush(&mut self) -> Result<()>;
+}
+
+/// A stream whose cursor can be repositioned.
+pub trait Seek {
+    /// Moves the cursor and returns the resulting absolute position.
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64>;
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> Read for R {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        Ok(std::io::Read::read(self, buf)?)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> Write for W {
+    fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+        Ok(std::io::Write::write_all(self, buf)?)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(std::io::Write::flush(self)?)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<S: std::io::Seek> Seek for S {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        let pos = match pos {
+            SeekFrom::Start(n) => std::io::SeekFrom::Start(n),
+        };
+        Ok(std::io::Seek::seek(self, pos)?)
+    }
+}
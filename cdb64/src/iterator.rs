@@ -1,18 +1,24 @@
-use std::io::{self, ErrorKind};
+use alloc::vec::Vec;
 
+use crate::Error;
 use crate::cdb::{Cdb, HEADER_SIZE, TableEntry};
 use crate::util::{ReaderAt, read_tuple};
 
 /// Represents a sequential iterator over a CDB database.
 ///
 /// This iterator borrows the Cdb instance immutably for its lifetime.
-pub struct CdbIterator<'cdb, R: ReaderAt, H: std::hash::Hasher + Default = crate::hash::CdbHash> {
+///
+/// `Item` is already `Result<(Vec<u8>, Vec<u8>), Error>`, so a caller
+/// writes `for entry in cdb.iter() { let (k, v) = entry?; }` directly —
+/// there's no separate `key()`/`value()`/`err()` accessor pattern to fold
+/// an out-of-band error into, since `next()` carries it inline.
+pub struct CdbIterator<'cdb, R: ReaderAt, H: core::hash::Hasher + Default = crate::hash::CdbHash> {
     cdb: &'cdb Cdb<R, H>,
     current_pos: u64,
     end_pos: u64,
 }
 
-impl<'cdb, R: ReaderAt, H: std::hash::Hasher + Default> CdbIterator<'cdb, R, H> {
+impl<'cdb, R: ReaderAt, H: core::hash::Hasher + Default> CdbIterator<'cdb, R, H> {
     /// Creates an iterator that borrows the Cdb immutably for its lifetime.
     pub fn new(cdb: &'cdb Cdb<R, H>) -> Self {
         let mut calculated_end_pos = u64::MAX;
@@ -39,8 +45,8 @@ impl<'cdb, R: ReaderAt, H: std::hash::Hasher + Default> CdbIterator<'cdb, R, H>
     }
 }
 
-impl<'a, R: ReaderAt, H: std::hash::Hasher + Default> Iterator for CdbIterator<'a, R, H> {
-    type Item = Result<(Vec<u8>, Vec<u8>), io::Error>;
+impl<'a, R: ReaderAt, H: core::hash::Hasher + Default> Iterator for CdbIterator<'a, R, H> {
+    type Item = Result<(Vec<u8>, Vec<u8>), Error>;
 
     /// Advances the iterator and reads the next key/value pair.
     /// Returns `Some(Ok((key, value)))` if a record was successfully read.
@@ -52,17 +58,21 @@ impl<'a, R: ReaderAt, H: std::hash::Hasher + Default> Iterator for CdbIterator<'
         }
 
         match read_tuple(&self.cdb.reader, self.current_pos) {
-            Ok((key_len, val_len)) => {
-                let record_data_offset = self.current_pos + 16;
-                let total_record_len_with_header = 16 + key_len + val_len;
+            Ok((key_len, val_len_raw)) => {
+                let compressed = val_len_raw & crate::compress::COMPRESSED_FLAG != 0;
+                let key_len = key_len as u64;
+                let val_len = (val_len_raw & !crate::compress::COMPRESSED_FLAG) as u64;
+                // Record header is 8 bytes (two u32 lengths), matching
+                // `read_tuple`/`CdbWriter::put`'s on-disk layout.
+                let record_data_offset = self.current_pos + 8;
+                let total_record_len_with_header = 8 + key_len + val_len;
 
                 if self
                     .current_pos
                     .saturating_add(total_record_len_with_header)
                     > self.end_pos
                 {
-                    return Some(Err(io::Error::new(
-                        ErrorKind::InvalidData,
+                    return Some(Err(Error::InvalidData(
                         "Record extends beyond expected data end",
                     )));
                 }
@@ -90,6 +100,15 @@ impl<'a, R: ReaderAt, H: std::hash::Hasher + Default> Iterator for CdbIterator<'
                 }
                 self.current_pos += total_record_len_with_header;
 
+                let val_buf = if compressed {
+                    match self.cdb.codec.decompress(&val_buf) {
+                        Ok(decoded) => decoded,
+                        Err(e) => return Some(Err(e)),
+                    }
+                } else {
+                    val_buf
+                };
+
                 Some(Ok((key_buf, val_buf)))
             }
             Err(e) => Some(Err(e)),
@@ -87,19 +87,117 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! ## `no_std`
+//!
+//! The `std` feature is enabled by default. Disabling it builds the crate in
+//! `no_std` mode, where the `std::io` dependency is replaced by the crate-local
+//! [`io`] traits and reads go through [`Error`] instead of `std::io::Error`. With
+//! `alloc` available an in-memory CDB image (a `&[u8]`) can still be queried, so
+//! lookups can run against a database held in flash or RAM on embedded targets.
+//! The `std::fs::File` and `Cursor<Vec<u8>>` readers, and the file-backed writer,
+//! are only compiled with the `std` feature.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[macro_use]
+extern crate alloc;
+
+pub mod io;
 
+mod bloom;
 mod cdb;
+mod classic;
+#[cfg(feature = "std")]
+mod checksum;
+mod compress;
+mod control;
+#[cfg(feature = "std")]
+mod format;
 mod hash;
 mod iterator;
+#[cfg(feature = "std")]
+mod split;
+mod store;
+#[cfg(feature = "serde")]
+mod typed;
 mod util;
+#[cfg(feature = "std")]
 mod writer;
 
 // re-exports
-pub use cdb::Cdb;
+pub use cdb::{Cdb, ColumnIter, GetIter};
+#[cfg(feature = "mmap")]
+pub use cdb::{AdvisePattern, MmapIter, MmapOptions};
+pub use classic::{ClassicCdb, ClassicIter, djb_hash32};
+#[cfg(feature = "std")]
+pub use cdb::ValueReader;
+#[cfg(feature = "std")]
+pub use split::SplitWriter;
+#[cfg(all(feature = "std", unix))]
+pub use split::SplitReaderAt;
+pub use store::ReadStore;
+#[cfg(feature = "std")]
+pub use store::{MemoryStore, MemoryStoreIter};
+#[cfg(feature = "serde")]
+pub use typed::{CborCodec, TypedCdb, ValueCodec};
+pub use compress::Codec;
 pub use hash::CdbHash;
 pub use iterator::CdbIterator;
 pub use util::ReaderAt;
-pub use writer::CdbWriter;
+#[cfg(feature = "std")]
+pub use writer::{CdbWriter, CdbWriterBuilder, WriteBatch};
+
+/// Streams every record from a database at `old_path` into a freshly written
+/// one at `new_path` with the [format stamp](CdbWriter::with_format_stamp)
+/// enabled, so a classic, unstamped database can be upgraded in place (under
+/// a new filename) to the self-describing format without reimplementing the
+/// read/write loop by hand.
+///
+/// `old_path` is opened with the plain, unstamped-or-stamped-agnostic
+/// [`Cdb::open`], so this also works as a one-way converter for a database
+/// that is already stamped, just re-stamping it with the current format
+/// version.
+#[cfg(feature = "std")]
+pub fn upgrade<H: core::hash::Hasher + Default>(
+    old_path: impl AsRef<std::path::Path>,
+    new_path: impl AsRef<std::path::Path>,
+) -> Result<(), Error> {
+    let old = Cdb::<std::fs::File, H>::open(old_path)?;
+    let mut writer = CdbWriter::<std::fs::File, H>::create(new_path)?.with_format_stamp(0);
+    for record in old.iter() {
+        let (key, value) = record?;
+        writer.put(&key, &value)?;
+    }
+    writer.finalize()
+}
+
+/// Streams every record from a classic, 32-bit-format cdb database at
+/// `src_path` into a freshly written native 64-bit one at `new_path`,
+/// migrating a dataset produced by the original `cdb` tool onto this
+/// crate's format.
+///
+/// Named distinctly from [`upgrade`] (which re-stamps an already-64-bit
+/// file) rather than overloaded onto it, since the two read entirely
+/// different on-disk layouts via [`ClassicCdb::open`] vs [`Cdb::open`] and
+/// Rust has no function overloading to pick between them by argument type.
+///
+/// Record order and duplicate keys are preserved, since this walks
+/// [`ClassicCdb::iter`]'s sequential pass over the source file rather than
+/// its hash table.
+#[cfg(feature = "std")]
+pub fn upgrade_classic<H: core::hash::Hasher + Default>(
+    src_path: impl AsRef<std::path::Path>,
+    new_path: impl AsRef<std::path::Path>,
+) -> Result<(), Error> {
+    let old = ClassicCdb::<std::fs::File>::open(src_path)?;
+    let mut writer = CdbWriter::<std::fs::File, H>::create(new_path)?.with_format_stamp(0);
+    for record in old.iter() {
+        let (key, value) = record?;
+        writer.put(&key, &value)?;
+    }
+    writer.finalize()
+}
 
 /// Errors that can occur when working with CDB databases.
 #[derive(Debug, thiserror::Error)]
@@ -110,9 +208,26 @@ pub enum Error {
     /// - File opening, reading, or writing
     /// - Memory mapping (when mmap feature is enabled)
     /// - Data serialization or deserialization
+    ///
+    /// Only available with the `std` feature; in `no_std` mode the crate-local
+    /// [`io`](crate::io) layer surfaces [`Error::UnexpectedEof`] and
+    /// [`Error::InvalidData`] instead.
+    #[cfg(feature = "std")]
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
 
+    /// The reader reached the end of the data before a full buffer could be filled.
+    ///
+    /// This is the `no_std` counterpart of an `UnexpectedEof` `std::io::Error`.
+    #[error("unexpected end of file")]
+    UnexpectedEof,
+
+    /// The on-disk bytes could not be interpreted as a valid CDB structure.
+    ///
+    /// The payload is a short static description of what failed to parse.
+    #[error("invalid data: {0}")]
+    InvalidData(&'static str),
+
     /// Indicates an attempt to operate on a writer that has already been finalized.
     ///
     /// Once `CdbWriter::finalize()` is called, no further `put()` operations are allowed.
@@ -158,4 +273,18 @@ pub enum Error {
     /// ```
     #[error("Writer has not been finalized yet")]
     WriterNotFinalized,
+
+    /// The checksum recorded by [`CdbWriter::finalize_with_checksum`](crate::CdbWriter::finalize_with_checksum)
+    /// does not match the checksum recomputed by
+    /// [`Cdb::open_verified`](crate::Cdb::open_verified), meaning the file's
+    /// data section or hash tables were corrupted or truncated after writing.
+    #[error("checksum mismatch: the database's data may be corrupted")]
+    ChecksumMismatch,
+
+    /// The file's [`format stamp`](crate::CdbWriter::with_format_stamp) names a
+    /// format version newer than this build of the crate understands.
+    ///
+    /// The payload is the unsupported version number found in the file.
+    #[error("unsupported format version {0}")]
+    UnsupportedFormat(u16),
 }
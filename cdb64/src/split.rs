@@ -0,0 +1,187 @@
+//! Multi-volume I/O for CDB databases larger than a single filesystem's
+//! file-size limit.
+//!
+//! A split database is a sequence of fixed-size parts named `<prefix>.000`,
+//! `<prefix>.001`, ... (every part but possibly the last is exactly
+//! `part_size` bytes), addressed through one continuous global offset.
+//! Splitting at a uniform stride rather than at a writer-chosen cutover
+//! point (e.g. right after the last whole record) keeps the offset-to-part
+//! mapping a plain division on both the write and read side, with no
+//! per-part length table to build or persist: `part_size` is the only
+//! number a reader needs to reopen a writer's parts.
+//!
+//! [`SplitWriter`] implements [`Write`]/[`Seek`] so [`CdbWriter`](crate::CdbWriter)
+//! can write a split database through [`CdbWriter::create_split`](crate::CdbWriter::create_split)
+//! without otherwise changing how it tracks offsets, and [`SplitReaderAt`]
+//! implements [`ReaderAt`] so [`Cdb`](crate::Cdb) can read one back unchanged.
+
+use std::fs::{File, OpenOptions};
+use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use crate::Error;
+use crate::util::ReaderAt;
+
+fn part_path(prefix: &Path, index: usize) -> PathBuf {
+    let mut name = prefix.as_os_str().to_owned();
+    name.push(format!(".{index:03}"));
+    PathBuf::from(name)
+}
+
+/// A [`ReaderAt`] that stitches the fixed-size parts written by
+/// [`CdbWriter::create_split`](crate::CdbWriter::create_split) back into one
+/// continuous byte space.
+///
+/// Only available on unix, matching the `File` [`ReaderAt`] impl this is
+/// built on.
+#[cfg(unix)]
+pub struct SplitReaderAt {
+    parts: Vec<File>,
+    part_size: u64,
+}
+
+#[cfg(unix)]
+impl SplitReaderAt {
+    /// Opens every `<prefix>.NNN` part in sequence, stopping at the first
+    /// index that doesn't exist.
+    ///
+    /// `part_size` must match the `max_bytes_per_file` the parts were
+    /// created with — it is not itself persisted anywhere in the database.
+    pub fn open(prefix: impl AsRef<Path>, part_size: u64) -> Result<Self, Error> {
+        let prefix = prefix.as_ref();
+        let mut parts = Vec::new();
+        loop {
+            match File::open(part_path(prefix, parts.len())) {
+                Ok(file) => parts.push(file),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => break,
+                Err(e) => return Err(Error::Io(e)),
+            }
+        }
+        if parts.is_empty() {
+            return Err(Error::InvalidData(
+                "no split parts found at the given prefix",
+            ));
+        }
+        Ok(Self { parts, part_size })
+    }
+
+    /// Total logical length across every part, for locating trailers the
+    /// same way [`Cdb::open`](crate::Cdb::open) does for a single file.
+    pub(crate) fn len(&self) -> Result<u64, Error> {
+        let mut total = 0u64;
+        for part in &self.parts {
+            total += part.metadata()?.len();
+        }
+        Ok(total)
+    }
+}
+
+#[cfg(unix)]
+impl ReaderAt for SplitReaderAt {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> crate::io::Result<usize> {
+        let part_index = (offset / self.part_size) as usize;
+        let Some(file) = self.parts.get(part_index) else {
+            return Ok(0);
+        };
+
+        let local_offset = offset % self.part_size;
+        let max_in_part = (self.part_size - local_offset).min(buf.len() as u64) as usize;
+        // Qualified because `File` also implements our own `ReaderAt::read_at`;
+        // an unqualified `use` of `FileExt` would make this call ambiguous.
+        Ok(std::os::unix::fs::FileExt::read_at(
+            file,
+            &mut buf[..max_in_part],
+            local_offset,
+        )?)
+    }
+}
+
+/// A [`Write`] + [`Seek`] sink that rolls writes over to a new
+/// `<prefix>.NNN` part every `part_size` bytes, read back by
+/// [`SplitReaderAt`].
+pub struct SplitWriter {
+    prefix: PathBuf,
+    part_size: u64,
+    parts: Vec<File>,
+    /// Current write/seek cursor, as a global offset across all parts.
+    pos: u64,
+    /// The furthest `pos` any write has reached, for `SeekFrom::End`.
+    len: u64,
+}
+
+impl SplitWriter {
+    pub(crate) fn create(prefix: impl AsRef<Path>, part_size: u64) -> Result<Self, Error> {
+        let prefix = prefix.as_ref().to_path_buf();
+        let first = Self::open_part(&prefix, 0)?;
+        Ok(Self {
+            prefix,
+            part_size,
+            parts: vec![first],
+            pos: 0,
+            len: 0,
+        })
+    }
+
+    fn open_part(prefix: &Path, index: usize) -> Result<File, Error> {
+        Ok(OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(part_path(prefix, index))?)
+    }
+
+    fn ensure_part(&mut self, index: usize) -> Result<(), Error> {
+        while self.parts.len() <= index {
+            let next = Self::open_part(&self.prefix, self.parts.len())?;
+            self.parts.push(next);
+        }
+        Ok(())
+    }
+}
+
+impl Write for SplitWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let part_index = (self.pos / self.part_size) as usize;
+        self.ensure_part(part_index)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+
+        let local_offset = self.pos % self.part_size;
+        let max_in_part = (self.part_size - local_offset).min(buf.len() as u64) as usize;
+
+        let part = &mut self.parts[part_index];
+        part.seek(SeekFrom::Start(local_offset))?;
+        let written = part.write(&buf[..max_in_part])?;
+
+        self.pos += written as u64;
+        self.len = self.len.max(self.pos);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        for part in &mut self.parts {
+            part.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl Seek for SplitWriter {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let new_pos = match pos {
+            SeekFrom::Start(n) => n as i64,
+            SeekFrom::End(n) => self.len as i64 + n,
+            SeekFrom::Current(n) => self.pos as i64 + n,
+        };
+
+        if new_pos < 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "seek to a negative position",
+            ));
+        }
+
+        self.pos = new_pos as u64;
+        Ok(self.pos)
+    }
+}
@@ -0,0 +1,98 @@
+//! A narrow read-only key-value interface so higher-level code (caches,
+//! routers) can be written once against [`ReadStore`] and swap [`Cdb`]
+//! for another backend — a mock in tests, or a different immutable store
+//! entirely — without conditional code at every call site.
+
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use core::hash::Hasher;
+
+use crate::Error;
+use crate::cdb::Cdb;
+use crate::util::ReaderAt;
+
+/// A read-only key-value store.
+///
+/// [`Cdb`] implements this directly; [`MemoryStore`] (std-only) gives tests
+/// a `HashMap`-backed stand-in with the same interface.
+pub trait ReadStore {
+    /// The iterator [`iter`](Self::iter) returns.
+    type Iter<'a>: Iterator<Item = Result<(Vec<u8>, Vec<u8>), Error>>
+    where
+        Self: 'a;
+
+    /// Returns the value for `key`, borrowed where the backend can do so
+    /// without a copy and owned where it can't — [`Cdb::get`] always
+    /// allocates, so its `ReadStore::get` always returns `Cow::Owned`.
+    fn get(&self, key: &[u8]) -> Result<Option<Cow<'_, [u8]>>, Error>;
+
+    /// Returns an iterator over every key-value pair in the store.
+    fn iter(&self) -> Self::Iter<'_>;
+}
+
+impl<R: ReaderAt, H: Hasher + Default> ReadStore for Cdb<R, H> {
+    type Iter<'a>
+        = crate::iterator::CdbIterator<'a, R, H>
+    where
+        Self: 'a;
+
+    fn get(&self, key: &[u8]) -> Result<Option<Cow<'_, [u8]>>, Error> {
+        Ok(Cdb::get(self, key)?.map(Cow::Owned))
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        Cdb::iter(self)
+    }
+}
+
+/// An in-memory [`ReadStore`] backed by a `HashMap`, for tests and mocks
+/// that want to stand in for a [`Cdb`] without writing one to disk.
+#[cfg(feature = "std")]
+#[derive(Debug, Default, Clone)]
+pub struct MemoryStore {
+    entries: std::collections::HashMap<Vec<u8>, Vec<u8>>,
+}
+
+#[cfg(feature = "std")]
+impl MemoryStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts or overwrites the value stored under `key`.
+    pub fn put(&mut self, key: impl Into<Vec<u8>>, value: impl Into<Vec<u8>>) {
+        self.entries.insert(key.into(), value.into());
+    }
+}
+
+#[cfg(feature = "std")]
+impl ReadStore for MemoryStore {
+    type Iter<'a> = MemoryStoreIter<'a>;
+
+    fn get(&self, key: &[u8]) -> Result<Option<Cow<'_, [u8]>>, Error> {
+        Ok(self.entries.get(key).map(|v| Cow::Borrowed(v.as_slice())))
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        MemoryStoreIter {
+            inner: self.entries.iter(),
+        }
+    }
+}
+
+/// Iterator over a [`MemoryStore`]'s entries, obtained from
+/// [`ReadStore::iter`].
+#[cfg(feature = "std")]
+pub struct MemoryStoreIter<'a> {
+    inner: std::collections::hash_map::Iter<'a, Vec<u8>, Vec<u8>>,
+}
+
+#[cfg(feature = "std")]
+impl Iterator for MemoryStoreIter<'_> {
+    type Item = Result<(Vec<u8>, Vec<u8>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(k, v)| Ok((k.clone(), v.clone())))
+    }
+}
@@ -1,7 +1,21 @@
-use std::io::{Error, ErrorKind, Result, Write};
+use alloc::vec::Vec;
+
+use crate::Error;
+use crate::io::{Result, Write};
 
 /// A trait for objects that can be read from at a specific offset.
 /// Similar to Go's `io.ReaderAt`.
+///
+/// Unlike the original implementation this trait no longer speaks `std::io`;
+/// reads resolve through [`crate::Error`] so the reader works in `no_std` mode.
+/// This is the `ByteReaderAt` half of a `no_std` reader abstraction: a
+/// positioned read into `&mut [u8]`, implemented for `std::fs::File` behind
+/// the `std` feature and for `&[u8]` unconditionally, so a firmware caller
+/// can open a CDB image held in flash or RAM with `#![no_std]` + `alloc`.
+/// The writer side is `std`-only: [`crate::writer::CdbWriter`] writes through
+/// `std::io::Write + Seek` (file-backed databases need real filesystem
+/// writes), and only the record-header helper [`write_tuple`] goes through
+/// the `no_std`-safe [`crate::io::Write`].
 pub trait ReaderAt {
     /// Reads up to `buf.len()` bytes into `buf` starting at `offset`.
     /// Returns the number of bytes read.
@@ -9,23 +23,16 @@ pub trait ReaderAt {
     fn read_at(&self, buf: &mut [u8], offset: u64) -> Result<usize>;
 
     /// Reads exactly `buf.len()` bytes into `buf` starting at `offset`.
-    /// If EOF is reached before `buf` is filled, an error of kind `ErrorKind::UnexpectedEof` is returned.
+    /// If EOF is reached before `buf` is filled, [`Error::UnexpectedEof`] is returned.
     fn read_exact_at(&self, mut buf: &mut [u8], mut offset: u64) -> Result<()> {
         while !buf.is_empty() {
-            match self.read_at(buf, offset) {
-                Ok(0) => {
-                    return Err(Error::new(
-                        ErrorKind::UnexpectedEof,
-                        "failed to fill whole buffer in read_exact_at",
-                    ));
-                }
-                Ok(n) => {
+            match self.read_at(buf, offset)? {
+                0 => return Err(Error::UnexpectedEof),
+                n => {
                     let tmp = buf; // Necessary due to borrow checker limitations with re-slicing buf in place
                     buf = &mut tmp[n..];
                     offset += n as u64;
                 }
-                Err(e) if e.kind() == ErrorKind::Interrupted => {} // Retry on interrupt
-                Err(e) => return Err(e),                           // Other errors
             }
         }
         Ok(())
@@ -33,11 +40,11 @@ pub trait ReaderAt {
 }
 
 /// Implement `ReaderAt` for `std::fs::File` on Unix-like systems.
-#[cfg(unix)]
+#[cfg(all(feature = "std", unix))]
 impl ReaderAt for std::fs::File {
     fn read_at(&self, buf: &mut [u8], offset: u64) -> Result<usize> {
         use std::os::unix::fs::FileExt;
-        FileExt::read_at(self, buf, offset)
+        Ok(FileExt::read_at(self, buf, offset)?)
     }
 }
 
@@ -52,7 +59,7 @@ impl ReaderAt for &'_ [u8] {
         }
 
         let remaining_in_self = self.len() - offset_usize;
-        let bytes_to_copy = std::cmp::min(buf.len(), remaining_in_self);
+        let bytes_to_copy = core::cmp::min(buf.len(), remaining_in_self);
 
         if bytes_to_copy > 0 {
             buf[..bytes_to_copy].copy_from_slice(&self[offset_usize..offset_usize + bytes_to_copy]);
@@ -62,12 +69,13 @@ impl ReaderAt for &'_ [u8] {
 }
 
 /// Implement `ReaderAt` for `std::io::Cursor<Vec<u8>>`.
+#[cfg(feature = "std")]
 impl ReaderAt for std::io::Cursor<Vec<u8>> {
     fn read_at(&self, buf: &mut [u8], offset: u64) -> Result<usize> {
         use std::io::{Read, Seek, SeekFrom};
         let mut inner_cursor = self.clone(); // Clone to avoid affecting the original cursor's position
         inner_cursor.seek(SeekFrom::Start(offset))?;
-        inner_cursor.read(buf)
+        Ok(inner_cursor.read(buf)?)
     }
 }
 
@@ -79,18 +87,12 @@ pub fn read_tuple<R: ReaderAt + ?Sized>(reader: &R, offset: u64) -> Result<(u32,
 
     // Safely convert parts of the buffer to u32.
     // These try_into calls should not fail if read_exact_at succeeded with an 8-byte buffer.
-    let first_bytes: [u8; 4] = buffer[0..4].try_into().map_err(|_| {
-        Error::new(
-            ErrorKind::InvalidData,
-            "Internal error: Failed to slice buffer for first u32",
-        )
-    })?;
-    let second_bytes: [u8; 4] = buffer[4..8].try_into().map_err(|_| {
-        Error::new(
-            ErrorKind::InvalidData,
-            "Internal error: Failed to slice buffer for second u32",
-        )
-    })?;
+    let first_bytes: [u8; 4] = buffer[0..4]
+        .try_into()
+        .map_err(|_| Error::InvalidData("Internal error: Failed to slice buffer for first u32"))?;
+    let second_bytes: [u8; 4] = buffer[4..8]
+        .try_into()
+        .map_err(|_| Error::InvalidData("Internal error: Failed to slice buffer for second u32"))?;
 
     let first = u32::from_le_bytes(first_bytes);
     let second = u32::from_le_bytes(second_bytes);
@@ -105,6 +107,22 @@ pub fn write_tuple<W: Write + ?Sized>(writer: &mut W, first: u32, second: u32) -
     Ok(())
 }
 
+/// Prepends column id `cf`'s little-endian bytes to `key`.
+///
+/// This is the namespacing scheme shared by
+/// [`CdbWriter::put_in`](crate::writer::CdbWriter::put_in),
+/// [`Cdb::get_in`](crate::Cdb::get_in), and
+/// [`Cdb::iter_in`](crate::Cdb::iter_in): a "column family" is nothing more
+/// than a 2-byte key prefix agreed on by both ends, so one database file can
+/// hold several independent keyspaces without a second format-level concept
+/// for the hash table or data section to know about.
+pub(crate) fn prefix_key(cf: u16, key: &[u8]) -> Vec<u8> {
+    let mut prefixed = Vec::with_capacity(2 + key.len());
+    prefixed.extend_from_slice(&cf.to_le_bytes());
+    prefixed.extend_from_slice(key);
+    prefixed
+}
+
 #[cfg(test)]
 mod tests {
     use super::*; // Import items from the parent module
@@ -186,7 +204,7 @@ mod tests {
         let mut buf = [0u8; 4];
         let result = data.read_exact_at(&mut buf, 0);
         assert!(result.is_err());
-        assert_eq!(result.err().unwrap().kind(), ErrorKind::UnexpectedEof);
+        assert!(matches!(result.err().unwrap(), Error::UnexpectedEof));
     }
 
     #[test]
@@ -195,7 +213,7 @@ mod tests {
         let mut buf = [0u8; 3];
         let result = data.read_exact_at(&mut buf, 3);
         assert!(result.is_err());
-        assert_eq!(result.err().unwrap().kind(), ErrorKind::UnexpectedEof);
+        assert!(matches!(result.err().unwrap(), Error::UnexpectedEof));
     }
 
     // Tests for read_tuple
@@ -237,13 +255,13 @@ mod tests {
 
         let result = read_tuple(&bytes_slice, 0);
         assert!(result.is_err());
-        assert_eq!(result.err().unwrap().kind(), ErrorKind::UnexpectedEof);
+        assert!(matches!(result.err().unwrap(), Error::UnexpectedEof));
 
         // Not enough bytes for even one u32
         let short_bytes_slice: &[u8] = &[1, 2, 3];
         let result_short = read_tuple(&short_bytes_slice, 0);
         assert!(result_short.is_err());
-        assert_eq!(result_short.err().unwrap().kind(), ErrorKind::UnexpectedEof);
+        assert!(matches!(result_short.err().unwrap(), Error::UnexpectedEof));
     }
 
     // Tests for write_tuple
@@ -281,7 +299,7 @@ mod tests {
 
             if let Some(fail_at) = self.fail_on_nth_read {
                 if self.read_count.get() == fail_at {
-                    return Err(std::io::Error::other("Simulated read error"));
+                    return Err(Error::InvalidData("Simulated read error"));
                 }
             }
 
@@ -328,7 +346,7 @@ mod tests {
         let mut buf = [0u8; 5];
         let result = reader.read_exact_at(&mut buf, 0);
         assert!(result.is_err());
-        assert_eq!(result.err().unwrap().kind(), ErrorKind::Other);
+        assert!(matches!(result.err().unwrap(), Error::InvalidData(_)));
         assert_eq!(buf[0..3], [1, 2, 3]);
         assert_eq!(reader.read_count.get(), 2);
     }
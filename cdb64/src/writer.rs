@@ -1,29 +1,276 @@
 use std::{
     fs::{File, OpenOptions},
     hash::Hasher,
-    io::{Seek, SeekFrom, Write},
+    io::{BufRead, BufWriter, Read, Seek, SeekFrom, Write},
     marker::PhantomData,
     path::Path,
 };
 
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
 use crate::{
-    Error,
+    Error, bloom, cdb, checksum,
     cdb::{Cdb, HEADER_SIZE, TableEntry},
+    compress::{COMPRESSED_FLAG, Codec, Trailer, parse_trailer},
+    control,
     hash::CdbHash,
-    util::write_tuple,
+    util::{ReaderAt, write_tuple},
 };
 
+/// Default bits-per-key for the Bloom filter, giving roughly a 1% false
+/// positive rate, matching the common leveldb recommendation.
+const DEFAULT_BLOOM_BITS_PER_KEY: usize = 10;
+
+/// Default compression level passed to codecs that accept one.
+const DEFAULT_COMPRESSION_LEVEL: i32 = 0;
+
+/// Default minimum value size, in bytes, before compression is attempted.
+///
+/// Small values rarely shrink and a few bytes are not worth the CPU, so values
+/// below this size are always stored verbatim.
+const DEFAULT_COMPRESS_THRESHOLD: usize = 64;
+
+/// Default capacity, in bytes, of the [`BufWriter`] wrapped around the sink
+/// when buffering is requested without an explicit size.
+const DEFAULT_WRITE_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Upper bound on a declared `klen`/`dlen` field accepted by
+/// [`CdbWriter::load_text`]. A hostile or corrupt stream could otherwise name a
+/// huge length and force an unbounded allocation before any bytes are read.
+const MAX_TEXT_FIELD_LEN: u64 = 1 << 30; // 1 GiB
+
+/// Builder for a buffered [`CdbWriter`] with capacity hints.
+///
+/// Created via [`CdbWriter::builder`]. Wrapping the sink in a [`BufWriter`]
+/// coalesces the many small `write_all` calls that `put` issues per record into
+/// far fewer syscalls, and [`with_capacity`](Self::with_capacity) pre-reserves
+/// the per-bucket hash-slot vectors so finalize does not reallocate the 256 slot
+/// lists as entries accumulate. The defaults reproduce the behavior of
+/// [`CdbWriter::new`].
+#[derive(Debug, Clone)]
+pub struct CdbWriterBuilder {
+    buffer_size: usize,
+    expected_entries: usize,
+}
+
+impl Default for CdbWriterBuilder {
+    fn default() -> Self {
+        CdbWriterBuilder {
+            buffer_size: DEFAULT_WRITE_BUFFER_SIZE,
+            expected_entries: 0,
+        }
+    }
+}
+
+impl CdbWriterBuilder {
+    /// Creates a builder with default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the capacity, in bytes, of the [`BufWriter`] wrapping the sink.
+    pub fn with_buffer_size(mut self, bytes: usize) -> Self {
+        self.buffer_size = bytes;
+        self
+    }
+
+    /// Hints the total number of entries so the per-bucket slot vectors can be
+    /// reserved up front, avoiding reallocation during finalize.
+    pub fn with_capacity(mut self, expected_entries: usize) -> Self {
+        self.expected_entries = expected_entries;
+        self
+    }
+
+    /// Builds a [`CdbWriter`] that writes through a [`BufWriter`] over `writer`.
+    pub fn build<W: Write + Seek, H: Hasher + Default>(
+        self,
+        writer: W,
+    ) -> Result<CdbWriter<BufWriter<W>, H>, Error> {
+        let mut writer = BufWriter::with_capacity(self.buffer_size, writer);
+        writer.seek(SeekFrom::Start(0))?;
+        let header_placeholder = vec![0u8; HEADER_SIZE as usize];
+        writer.write_all(&header_placeholder)?;
+
+        // Spread the expected-entry hint evenly across the 256 buckets: each
+        // reserves the mean, rounded up. The djb hash distributes keys roughly
+        // uniformly; a bucket that receives more than that grows as usual.
+        let per_table = self.expected_entries.div_ceil(256);
+        let mut entries_by_table: [Vec<Entry>; 256] = [const { Vec::new() }; 256];
+        if per_table > 0 {
+            for table in entries_by_table.iter_mut() {
+                table.reserve(per_table);
+            }
+        }
+
+        Ok(CdbWriter {
+            writer,
+            entries_by_table,
+            is_finalized: false,
+            current_data_offset: HEADER_SIZE,
+            codec: Codec::Stored,
+            codec_level: DEFAULT_COMPRESSION_LEVEL,
+            compress_threshold: DEFAULT_COMPRESS_THRESHOLD,
+            bloom_bits_per_key: None,
+            bloom_hashes: Vec::new(),
+            swiss_table: false,
+            format_stamp: None,
+            columns_used: std::collections::BTreeSet::new(),
+            _hasher: PhantomData,
+        })
+    }
+}
+
+/// Reads a single byte, returning `None` at end of input.
+fn read_one_byte<Rd: Read>(reader: &mut Rd) -> Result<Option<u8>, Error> {
+    let mut byte = [0u8; 1];
+    loop {
+        match reader.read(&mut byte) {
+            Ok(0) => return Ok(None),
+            Ok(_) => return Ok(Some(byte[0])),
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+/// Parses a non-empty run of ASCII digits into a `u64`.
+fn parse_decimal(bytes: &[u8]) -> Result<u64, Error> {
+    if bytes.is_empty() {
+        return Err(Error::InvalidData("empty length field"));
+    }
+    let mut value: u64 = 0;
+    for &b in bytes {
+        if !b.is_ascii_digit() {
+            return Err(Error::InvalidData("non-digit in length field"));
+        }
+        value = value
+            .checked_mul(10)
+            .and_then(|v| v.checked_add((b - b'0') as u64))
+            .ok_or(Error::InvalidData("length field overflow"))?;
+    }
+    Ok(value)
+}
+
 #[derive(Debug)]
 struct Entry {
     hash_val: u64,
     offset: u64,
 }
 
+/// Places `entries` into a `num_slots`-sized table via linear probing
+/// (wrapping within the table on collision), returning the SwissTable
+/// control-byte array (if `swiss_table`) and the `(hash, data_offset)` slot
+/// pairs, ready to write out at that table's pre-assigned file offset.
+///
+/// Doesn't touch `self` or any other table's state, so this is what
+/// [`CdbWriter::write_footer_and_header`] can safely run across all 256
+/// tables in parallel.
+fn build_table_slots(
+    entries: &[Entry],
+    num_slots: usize,
+    swiss_table: bool,
+) -> (Option<Vec<u8>>, Vec<(u64, u64)>) {
+    if num_slots == 0 {
+        return (None, Vec::new());
+    }
+
+    let mut slots_data = vec![(0u64, 0u64); num_slots];
+    let mut control_bytes = swiss_table.then(|| vec![control::EMPTY; num_slots]);
+
+    for entry in entries {
+        let mut slot_idx = (entry.hash_val >> 8) % (num_slots as u64);
+        if swiss_table {
+            // The reader walks whole control-byte groups from the home slot's
+            // group and stops at the first group holding an empty slot, so
+            // probing starts at the first slot of that group.
+            slot_idx -= slot_idx % control::GROUP as u64;
+        }
+        loop {
+            if slots_data[slot_idx as usize].1 == 0 {
+                // .1 is offset, 0 means empty slot
+                slots_data[slot_idx as usize] = (entry.hash_val, entry.offset);
+                if let Some(control_bytes) = control_bytes.as_mut() {
+                    control_bytes[slot_idx as usize] = control::tag(entry.hash_val);
+                }
+                break;
+            }
+            slot_idx = (slot_idx + 1) % (num_slots as u64);
+        }
+    }
+
+    (control_bytes, slots_data)
+}
+
+/// A batch of `put` calls accumulated in memory and applied to a
+/// [`CdbWriter`] in one [`write_batch`](CdbWriter::write_batch) call.
+///
+/// Compared to calling [`put`](CdbWriter::put) directly for each record, a
+/// batch gives a single fallible commit point: if an I/O error strikes
+/// partway through, `write_batch` hands the batch straight back so it can be
+/// retried against a fresh writer instead of leaving the caller to figure out
+/// which records already landed. `write_batch` also sorts the batch by
+/// `hash_val & 0xff` before writing, so records destined for the same of the
+/// 256 hash tables land contiguously in the data section -- via a stable
+/// sort, so records that land in the same table keep their relative
+/// insertion order. That stability is also what lets several independently
+/// produced batches (say, one per shard of a parallel producer) be handed to
+/// one finalizing writer via separate `write_batch` calls and still come out
+/// with each batch's own ordering intact, with no merge step of their own
+/// required.
+#[derive(Debug, Default)]
+pub struct WriteBatch {
+    entries: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl WriteBatch {
+    /// Creates an empty batch.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues a key-value pair to be written by the next
+    /// [`write_batch`](CdbWriter::write_batch) call.
+    ///
+    /// As with [`CdbWriter::put`], duplicate keys and empty keys/values are
+    /// allowed.
+    pub fn put(&mut self, key: &[u8], value: &[u8]) {
+        self.entries.push((key.to_vec(), value.to_vec()));
+    }
+
+    /// Returns the number of queued records.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the batch has no queued records.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
 pub struct CdbWriter<W: Write + Seek, H: Hasher + Default = CdbHash> {
     writer: W,
     entries_by_table: [Vec<Entry>; 256],
     is_finalized: bool,
     current_data_offset: u64,
+    codec: Codec,
+    codec_level: i32,
+    compress_threshold: usize,
+    /// Bits-per-key for the optional Bloom filter; `None` disables it.
+    bloom_bits_per_key: Option<usize>,
+    /// Per-key hashes accumulated for the Bloom filter, only when enabled.
+    bloom_hashes: Vec<u64>,
+    /// Whether hash tables are written in the SwissTable-style control-byte
+    /// layout instead of the classic bare slot array.
+    swiss_table: bool,
+    /// The hasher id to stamp when [`with_format_stamp`](Self::with_format_stamp)
+    /// is set; `None` disables the format stamp trailer.
+    format_stamp: Option<u16>,
+    /// Column ids passed to [`put_in`](Self::put_in) so far, for
+    /// [`columns`](Self::columns) to report back. Tracked in memory only --
+    /// there is no on-disk record of which prefixes are in use.
+    columns_used: std::collections::BTreeSet<u16>,
     _hasher: PhantomData<H>,
 }
 
@@ -37,9 +284,143 @@ impl<H: Hasher + Default> CdbWriter<File, H> {
 
         Self::new(file)
     }
+
+    /// Reopens an already-finalized CDB file at `path` so more records can be
+    /// inserted with `put`.
+    ///
+    /// Shorthand for opening the file for read-write access and passing it to
+    /// [`from_existing`](Self::from_existing).
+    pub fn open_append(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        Self::from_existing(file)
+    }
+
+    /// Reconstructs writer state from an already-finalized database so `put`
+    /// can keep inserting records into it.
+    ///
+    /// Reads the 256-entry header to find the start of the hash-table region
+    /// (the smallest non-zero `TableEntry::offset`, or the end of the file if
+    /// every table is empty), then walks the data section from [`HEADER_SIZE`]
+    /// up to that boundary, parsing each `(klen: u32, vlen: u32, key, value)`
+    /// record and re-hashing its key to repopulate `entries_by_table`. The
+    /// hash tables, Bloom filter, and trailer occupying the rest of the file
+    /// are left in place — they are simply overwritten by the next
+    /// `finalize()`, which regenerates them from scratch over the full key set.
+    ///
+    /// The codec and table layout are picked back up from the existing
+    /// trailer, if any, so appended records stay consistent with what's
+    /// already on disk. The codec's compression level and `compress_threshold`
+    /// are not persisted in the trailer, so they fall back to their defaults;
+    /// call [`with_compression`](Self::with_compression) again after this if
+    /// the database was built with non-default values. Likewise, the exact
+    /// `bits_per_key` passed to [`with_bloom`](Self::with_bloom) is not
+    /// stored — only the resulting bit count — so a present Bloom filter is
+    /// kept enabled with a `bits_per_key` recovered by dividing the existing
+    /// bit count back out over the key count, which reproduces the filter's
+    /// size but not necessarily the exact original argument.
+    pub fn from_existing(mut file: File) -> Result<Self, Error> {
+        let file_len = file.metadata()?.len();
+
+        let mut header_buf = vec![0u8; HEADER_SIZE as usize];
+        file.read_exact_at(&mut header_buf, 0)?;
+
+        let mut hash_table_start = file_len;
+        for i in 0..256 {
+            let offset = u64::from_le_bytes(header_buf[i * 16..i * 16 + 8].try_into().map_err(
+                |_| Error::InvalidData("Failed to slice offset from header"),
+            )?);
+            // Empty tables (offset 0) don't bound the data section.
+            if offset > 0 {
+                hash_table_start = hash_table_start.min(offset);
+            }
+        }
+
+        // The compression/layout trailer isn't necessarily the last
+        // `TRAILER_LEN` bytes of the file: a format stamp and/or checksum
+        // trailer may be stacked after it at EOF (see `format.rs`'s
+        // documented stacking order), the same way `Cdb::open`/`open_verified`
+        // peel those off before looking for it.
+        let trailer_search_len = cdb::check_format_stamp(&file, file_len)?;
+        let trailer_search_len = checksum::peel_checksum_trailer(&file, trailer_search_len)?;
+
+        let trailer = if trailer_search_len >= HEADER_SIZE + crate::compress::TRAILER_LEN as u64 {
+            let mut tail = [0u8; crate::compress::TRAILER_LEN];
+            file.read_exact_at(
+                &mut tail,
+                trailer_search_len - crate::compress::TRAILER_LEN as u64,
+            )?;
+            parse_trailer(&tail)?
+        } else {
+            None
+        };
+
+        let mut entries_by_table: [Vec<Entry>; 256] = [const { Vec::new() }; 256];
+        let mut bloom_hashes = Vec::new();
+        let mut pos = HEADER_SIZE;
+        while pos < hash_table_start {
+            let (key_len, val_len_field) = crate::util::read_tuple(&file, pos)?;
+            let val_len = (val_len_field & !COMPRESSED_FLAG) as u64;
+            let key_len = key_len as u64;
+
+            let key_start = pos + 8;
+            let mut key = vec![0u8; key_len as usize];
+            file.read_exact_at(&mut key, key_start)?;
+
+            let mut hasher = H::default();
+            hasher.write(&key);
+            let hash_val = hasher.finish();
+            let table_idx = (hash_val & 0xff) as usize;
+            entries_by_table[table_idx].push(Entry {
+                hash_val,
+                offset: pos,
+            });
+            bloom_hashes.push(hash_val);
+
+            pos = key_start + key_len + val_len;
+        }
+
+        let (codec, swiss_table, bloom_bits_per_key) = match trailer {
+            Some(t) => {
+                let bits_per_key = (t.bloom_nbits > 0).then(|| {
+                    (t.bloom_nbits / (bloom_hashes.len() as u64).max(1)).max(1) as usize
+                });
+                (t.codec, t.swiss_table, bits_per_key)
+            }
+            None => (Codec::Stored, false, None),
+        };
+        if bloom_bits_per_key.is_none() {
+            bloom_hashes.clear();
+        }
+
+        // `put` no longer seeks before writing (see `put`'s doc comment), so the
+        // underlying file's write cursor must be moved to the resume point here.
+        file.seek(SeekFrom::Start(pos))?;
+
+        Ok(CdbWriter {
+            writer: file,
+            entries_by_table,
+            is_finalized: false,
+            current_data_offset: pos,
+            codec,
+            codec_level: DEFAULT_COMPRESSION_LEVEL,
+            compress_threshold: DEFAULT_COMPRESS_THRESHOLD,
+            bloom_bits_per_key,
+            bloom_hashes,
+            swiss_table,
+            format_stamp: None,
+            columns_used: std::collections::BTreeSet::new(),
+            _hasher: PhantomData,
+        })
+    }
 }
 
 impl<W: Write + Seek, H: Hasher + Default> CdbWriter<W, H> {
+    /// Returns a [`CdbWriterBuilder`] for configuring write buffering and
+    /// capacity hints before wrapping a sink.
+    pub fn builder() -> CdbWriterBuilder {
+        CdbWriterBuilder::new()
+    }
+
     pub fn new(mut writer: W) -> Result<Self, Error> {
         writer.seek(SeekFrom::Start(0))?;
         let header_placeholder = vec![0u8; HEADER_SIZE as usize];
@@ -50,10 +431,142 @@ impl<W: Write + Seek, H: Hasher + Default> CdbWriter<W, H> {
             entries_by_table: [const { Vec::new() }; 256],
             is_finalized: false,
             current_data_offset: HEADER_SIZE,
+            codec: Codec::Stored,
+            codec_level: DEFAULT_COMPRESSION_LEVEL,
+            compress_threshold: DEFAULT_COMPRESS_THRESHOLD,
+            bloom_bits_per_key: None,
+            bloom_hashes: Vec::new(),
+            swiss_table: false,
+            format_stamp: None,
+            columns_used: std::collections::BTreeSet::new(),
             _hasher: PhantomData,
         })
     }
 
+    /// Shorthand for [`builder`](Self::builder)`().with_buffer_size(capacity).build(writer)`:
+    /// wraps `writer` in a [`BufWriter`] of the given capacity so the many small
+    /// `write_all` calls `put` issues per record coalesce into far fewer syscalls.
+    pub fn with_capacity(
+        writer: W,
+        capacity: usize,
+    ) -> Result<CdbWriter<BufWriter<W>, H>, Error> {
+        CdbWriterBuilder::new()
+            .with_buffer_size(capacity)
+            .build(writer)
+    }
+
+    /// Enables transparent per-value compression for every subsequent `put`.
+    ///
+    /// Values at least [`compress_threshold`](Self::with_compress_threshold)
+    /// bytes long are compressed with `codec`; the high bit of the stored
+    /// value-length flags those records so the reader can decompress them, while
+    /// keys and the slot layout are untouched. The chosen codec is recorded in a
+    /// trailer and validated by the reader on open.
+    ///
+    /// The default codec is [`Codec::Stored`], which sets no flag and writes no
+    /// trailer, keeping the file byte-for-byte identical to the uncompressed
+    /// format.
+    ///
+    /// `Codec` already covers LZ4 (`lz4` feature) and Snappy (`snappy`
+    /// feature) behind this one entry point, so there's no separate
+    /// `snap`-only codec to add on top of it.
+    pub fn with_compression(mut self, codec: Codec, level: i32) -> Self {
+        self.codec = codec;
+        self.codec_level = level;
+        self
+    }
+
+    /// Sets the minimum value size, in bytes, before compression is attempted.
+    ///
+    /// Values smaller than this are always stored verbatim. Has no effect unless
+    /// a codec other than [`Codec::Stored`] is configured via
+    /// [`with_compression`](Self::with_compression).
+    pub fn with_compress_threshold(mut self, bytes: usize) -> Self {
+        self.compress_threshold = bytes;
+        self
+    }
+
+    /// Builds a Bloom filter over all keys, stored in a region the reader
+    /// consults before probing so that absent keys are rejected without a slot
+    /// read.
+    ///
+    /// `bits_per_key` trades space for accuracy; the default of
+    /// [`DEFAULT_BLOOM_BITS_PER_KEY`] (10) gives roughly a 1% false-positive
+    /// rate. Readers of filter-less files are unaffected.
+    pub fn with_bloom(mut self, bits_per_key: usize) -> Self {
+        self.bloom_bits_per_key = Some(bits_per_key.max(1));
+        self
+    }
+
+    /// Enables the Bloom filter with the default [`DEFAULT_BLOOM_BITS_PER_KEY`].
+    pub fn with_default_bloom(self) -> Self {
+        self.with_bloom(DEFAULT_BLOOM_BITS_PER_KEY)
+    }
+
+    /// Writes hash tables in the SwissTable-style control-byte layout instead
+    /// of the classic bare `(hash, offset)` slot array.
+    ///
+    /// Each table is preceded by one control byte per slot (the low 7 bits of
+    /// the slot's hash, or [`control::EMPTY`] for an empty slot), letting the
+    /// reader load a [`control::GROUP`]-sized run of control bytes in one read
+    /// and pick out its candidate slots with a single SIMD or SWAR compare.
+    /// A table only holds hashes that share their low byte (the table index),
+    /// so every full slot in a table carries the same tag: the compare skips
+    /// empty slots, and each full slot in a probed group still gets a
+    /// full-hash check. The choice is recorded in the trailer so the reader
+    /// knows which layout to probe.
+    pub fn with_swiss_table(mut self) -> Self {
+        self.swiss_table = true;
+        self
+    }
+
+    /// Enables the format/version stamp trailer, so [`Cdb::open`](crate::Cdb::open)
+    /// can reject a file written with a newer, incompatible format version.
+    ///
+    /// `hasher_id` is an opaque identifier for the `Hasher` this writer was
+    /// built with; it is recorded for diagnostic purposes (e.g. `0` for the
+    /// built-in [`CdbHash`]) but is not itself checked on open, since a
+    /// generic `H: Hasher + Default` has no runtime identity to compare it
+    /// against. A classic file written without this trailer keeps opening
+    /// normally — the stamp is purely additive.
+    pub fn with_format_stamp(mut self, hasher_id: u16) -> Self {
+        self.format_stamp = Some(hasher_id);
+        self
+    }
+
+    /// Appends the format stamp trailer, if [`with_format_stamp`](Self::with_format_stamp)
+    /// was used, recording the current codec/layout choices and whether a
+    /// checksum trailer sits beneath it. A no-op otherwise.
+    fn write_format_trailer(&mut self, checksum_present: bool) -> Result<(), Error> {
+        let Some(hasher_id) = self.format_stamp else {
+            return Ok(());
+        };
+
+        let mut flags = 0u32;
+        if self.codec != Codec::Stored {
+            flags |= crate::format::FLAG_COMPRESSED;
+        }
+        if checksum_present {
+            flags |= crate::format::FLAG_CHECKSUM;
+        }
+        if self.swiss_table {
+            flags |= crate::format::FLAG_SWISS_TABLE;
+        }
+        if self.bloom_bits_per_key.is_some() {
+            flags |= crate::format::FLAG_BLOOM;
+        }
+
+        let stamp = crate::format::FormatStamp {
+            version: crate::format::FORMAT_VERSION,
+            hasher_id,
+            flags,
+        };
+
+        self.writer.seek(SeekFrom::End(0))?;
+        self.writer.write_all(&stamp.to_bytes())?;
+        Ok(())
+    }
+
     /// Inserts a key-value pair into the CDB database.
     ///
     /// # Arguments
@@ -91,12 +604,29 @@ impl<W: Write + Seek, H: Hasher + Default> CdbWriter<W, H> {
             return Err(Error::WriterFinalized);
         }
 
-        self.writer
-            .seek(SeekFrom::Start(self.current_data_offset))?;
-        // Write key and value lengths as u64
-        write_tuple(&mut self.writer, key.len() as u64, value.len() as u64)?;
+        // The data section is written strictly sequentially, so `current_data_offset`
+        // always matches the stream position already — no seek needed here. Only
+        // `write_footer_and_header` jumps around, once finalization begins.
+
+        // Compress only when a codec is set and the value is worth it; a payload
+        // that fails to shrink is kept verbatim. Compressed records are flagged
+        // by the high bit of the stored value-length.
+        let compressed = if self.codec != Codec::Stored && value.len() >= self.compress_threshold {
+            let candidate = self.codec.compress(value, self.codec_level)?;
+            (candidate.len() < value.len()).then_some(candidate)
+        } else {
+            None
+        };
+        let stored_value: &[u8] = compressed.as_deref().unwrap_or(value);
+        let val_len_field =
+            stored_value.len() as u32 | if compressed.is_some() { COMPRESSED_FLAG } else { 0 };
+
+        // Record header is two little-endian u32 lengths (8 bytes total),
+        // matching `read_tuple`/`write_tuple` and what `Cdb::get`/`CdbIterator`
+        // read back.
+        write_tuple(&mut self.writer, key.len() as u32, val_len_field)?;
         self.writer.write_all(key)?;
-        self.writer.write_all(value)?;
+        self.writer.write_all(stored_value)?;
 
         let mut hasher = H::default();
         hasher.write(key);
@@ -108,8 +638,172 @@ impl<W: Write + Seek, H: Hasher + Default> CdbWriter<W, H> {
             offset: self.current_data_offset,
         });
 
-        // Adjust offset calculation: 16 bytes for (u64, u64) lengths
-        self.current_data_offset += 16 + key.len() as u64 + value.len() as u64;
+        // Record the key hash for the Bloom filter when enabled; the reader uses
+        // the same full 64-bit hash, so the probe sequence matches at query time.
+        if self.bloom_bits_per_key.is_some() {
+            self.bloom_hashes.push(hash_val);
+        }
+
+        // Record header is 8 bytes (two u32 lengths), not 16 -- must match
+        // the stride `read_tuple`/`CdbIterator` use to walk the data section.
+        self.current_data_offset += 8 + key.len() as u64 + stored_value.len() as u64;
+        Ok(())
+    }
+
+    /// Writes `key`/`value` into column `cf`, namespacing it by prefixing
+    /// the stored key with `cf`'s 2 raw little-endian bytes.
+    ///
+    /// This is [`put`](Self::put) underneath, with that prefix transparently
+    /// applied -- there's no second hash table or data section per column,
+    /// just one shared keyspace split by a prefix both
+    /// [`Cdb::get_in`](crate::Cdb::get_in) and
+    /// [`Cdb::iter_in`](crate::Cdb::iter_in) agree on. This is namespacing by
+    /// convention, not a collision-proof encoding: a plain
+    /// [`put`](Self::put) whose key happens to start with the same 2 bytes
+    /// stores to the exact same slot as `put_in(cf, ..)`, and two `put_in`
+    /// calls under different `cf`s only avoid each other because their
+    /// prefixes differ, not because the format tags prefixed keys as
+    /// distinct from unprefixed ones.
+    pub fn put_in(&mut self, cf: u16, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        self.columns_used.insert(cf);
+        self.put(&crate::util::prefix_key(cf, key), value)
+    }
+
+    /// Returns every column id passed to [`put_in`](Self::put_in) so far, in
+    /// ascending order.
+    ///
+    /// Tracked purely in memory: a writer reconstructed by
+    /// [`from_existing`](Self::from_existing) starts with an empty set even
+    /// if the reopened file already has columned keys in it, the same way
+    /// [`from_existing`](Self::from_existing) can only approximate the
+    /// Bloom filter's original `bits_per_key` rather than recover it exactly.
+    pub fn columns(&self) -> impl Iterator<Item = u16> + '_ {
+        self.columns_used.iter().copied()
+    }
+
+    /// Applies a [`WriteBatch`] as a single fallible commit: every queued
+    /// record is written via [`put`](Self::put), sorted by `hash_val & 0xff`
+    /// first so records landing in the same hash table are written
+    /// contiguously.
+    ///
+    /// # Errors
+    ///
+    /// If a `put` fails partway through, the batch is handed back unconsumed
+    /// alongside the error so it can be retried against a fresh writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cdb64::{CdbWriter, CdbHash, WriteBatch};
+    /// use std::io::Cursor;
+    ///
+    /// let mut batch = WriteBatch::new();
+    /// batch.put(b"key1", b"value1");
+    /// batch.put(b"key2", b"value2");
+    ///
+    /// let mut writer = CdbWriter::<_, CdbHash>::new(Cursor::new(Vec::new())).unwrap();
+    /// writer.write_batch(batch).unwrap();
+    /// writer.finalize().unwrap();
+    /// ```
+    pub fn write_batch(&mut self, batch: WriteBatch) -> Result<(), (WriteBatch, Error)> {
+        if self.is_finalized {
+            return Err((batch, Error::WriterFinalized));
+        }
+
+        let mut order: Vec<usize> = (0..batch.entries.len()).collect();
+        order.sort_by_key(|&i| {
+            let mut hasher = H::default();
+            hasher.write(&batch.entries[i].0);
+            hasher.finish() & 0xff
+        });
+
+        for i in order {
+            let (key, value) = &batch.entries[i];
+            if let Err(e) = self.put(key, value) {
+                return Err((batch, e));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Loads records from a reader in the classic `cdbmake` text format.
+    ///
+    /// Each record is `+klen,dlen:key->data\n`, and a blank line terminates the
+    /// stream (a bare end-of-input is also tolerated). `klen` and `dlen` are
+    /// decimal byte counts; the key and data are read verbatim, so they may
+    /// contain any bytes including newlines. The declared lengths are validated
+    /// against what is actually read and capped at [`MAX_TEXT_FIELD_LEN`] to
+    /// avoid unbounded allocation, with a descriptive [`Error::InvalidData`] on
+    /// any malformed input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cdb64::{CdbWriter, CdbHash};
+    /// use std::io::Cursor;
+    ///
+    /// let mut writer = CdbWriter::<_, CdbHash>::new(Cursor::new(Vec::new())).unwrap();
+    /// writer.load_text(&b"+3,5:one->hello\n\n"[..]).unwrap();
+    /// writer.finalize().unwrap();
+    /// ```
+    pub fn load_text<Rd: BufRead>(&mut self, mut reader: Rd) -> Result<(), Error> {
+        // Input that ends before a declared length is used up is malformed
+        // input, not an I/O failure.
+        let short = |e: std::io::Error| match e.kind() {
+            std::io::ErrorKind::UnexpectedEof => {
+                Error::InvalidData("input ends before the record's declared length")
+            }
+            _ => Error::Io(e),
+        };
+        loop {
+            // Each record starts with '+'; a blank line ends the stream.
+            match read_one_byte(&mut reader)? {
+                None => break, // tolerate a missing blank-line terminator
+                Some(b'\n') => break,
+                Some(b'+') => {}
+                Some(_) => {
+                    return Err(Error::InvalidData("expected '+' at start of record"));
+                }
+            }
+
+            let mut header = Vec::new();
+            let n = reader.read_until(b':', &mut header)?;
+            if n == 0 || header.last() != Some(&b':') {
+                return Err(Error::InvalidData("unterminated record header"));
+            }
+            header.pop(); // drop the trailing ':'
+
+            let comma = header
+                .iter()
+                .position(|&c| c == b',')
+                .ok_or(Error::InvalidData("missing ',' in record header"))?;
+            let klen = parse_decimal(&header[..comma])?;
+            let dlen = parse_decimal(&header[comma + 1..])?;
+            if klen > MAX_TEXT_FIELD_LEN || dlen > MAX_TEXT_FIELD_LEN {
+                return Err(Error::InvalidData("record length field too large"));
+            }
+
+            let mut key = vec![0u8; klen as usize];
+            reader.read_exact(&mut key).map_err(short)?;
+
+            let mut arrow = [0u8; 2];
+            reader.read_exact(&mut arrow).map_err(short)?;
+            if &arrow != b"->" {
+                return Err(Error::InvalidData("expected '->' between key and data"));
+            }
+
+            let mut data = vec![0u8; dlen as usize];
+            reader.read_exact(&mut data).map_err(short)?;
+
+            let mut newline = [0u8; 1];
+            reader.read_exact(&mut newline).map_err(short)?;
+            if newline[0] != b'\n' {
+                return Err(Error::InvalidData("expected newline after record"));
+            }
+
+            self.put(&key, &data)?;
+        }
         Ok(())
     }
 
@@ -123,44 +817,107 @@ impl<W: Write + Seek, H: Hasher + Default> CdbWriter<W, H> {
         let mut final_header_entries = [TableEntry::default(); 256];
         let mut current_pos_for_hash_tables = self.current_data_offset;
 
+        // Pass 1: each table's byte size depends only on its own entry
+        // count, not on where its slots end up -- so every table's final
+        // absolute offset can be assigned by prefix-summing those sizes up
+        // front, before a single slot is placed. That's what lets pass 2
+        // below build all 256 tables' slot buffers independently instead of
+        // threading a shared write cursor through the placement loop.
+        let mut table_layout = [(0usize, 0u64); 256];
         for (i, entries_in_this_table) in self.entries_by_table.iter().enumerate() {
             if entries_in_this_table.is_empty() {
-                final_header_entries[i] = TableEntry {
-                    offset: 0,
-                    length: 0,
-                };
                 continue;
             }
+            let raw_slots = entries_in_this_table.len() * 2;
+            // The SwissTable layout probes a whole control-byte group (16
+            // slots) at a time, so the table is padded up to a group boundary
+            // to keep every group fully in-bounds.
+            let num_slots = if self.swiss_table {
+                raw_slots.next_multiple_of(control::GROUP)
+            } else {
+                raw_slots
+            };
+            let control_bytes_len = if self.swiss_table { num_slots } else { 0 };
+            table_layout[i] = (num_slots, current_pos_for_hash_tables);
+            current_pos_for_hash_tables += (control_bytes_len + num_slots * 16) as u64;
+        }
 
-            let num_slots = entries_in_this_table.len() * 2;
-            let mut slots_data = vec![(0u64, 0u64); num_slots];
+        // Pass 2: place every table's entries into its slot buffer. The 256
+        // tables are fully independent (an entry's table is `hash & 0xff`),
+        // and every `entry.offset` here is a data-region position computed
+        // before this pass started, so workers share no mutable state --
+        // with the `rayon` feature this runs across a thread pool instead
+        // of one table at a time.
+        #[cfg(feature = "rayon")]
+        let built_tables: Vec<(Option<Vec<u8>>, Vec<(u64, u64)>)> = self
+            .entries_by_table
+            .par_iter()
+            .zip(table_layout.par_iter())
+            .map(|(entries_in_this_table, &(num_slots, _offset))| {
+                build_table_slots(entries_in_this_table, num_slots, self.swiss_table)
+            })
+            .collect();
+        #[cfg(not(feature = "rayon"))]
+        let built_tables: Vec<(Option<Vec<u8>>, Vec<(u64, u64)>)> = self
+            .entries_by_table
+            .iter()
+            .zip(table_layout.iter())
+            .map(|(entries_in_this_table, &(num_slots, _offset))| {
+                build_table_slots(entries_in_this_table, num_slots, self.swiss_table)
+            })
+            .collect();
 
+        // Pass 3: write every table's buffer at its pre-assigned offset, in
+        // table order.
+        for (i, (control_bytes, slots_data)) in built_tables.into_iter().enumerate() {
+            let (num_slots, offset) = table_layout[i];
             final_header_entries[i] = TableEntry {
-                offset: current_pos_for_hash_tables,
+                offset,
                 length: num_slots as u64, // num_slots is the count of (u64, u64) pairs
             };
-
-            for entry in entries_in_this_table {
-                let mut slot_idx = (entry.hash_val >> 8) % (num_slots as u64);
-                loop {
-                    if slots_data[slot_idx as usize].1 == 0 {
-                        // .1 is offset, 0 means empty slot
-                        slots_data[slot_idx as usize] = (entry.hash_val, entry.offset);
-                        break;
-                    }
-                    slot_idx = (slot_idx + 1) % (num_slots as u64);
-                }
+            if num_slots == 0 {
+                continue;
             }
 
-            self.writer
-                .seek(SeekFrom::Start(current_pos_for_hash_tables))?;
+            self.writer.seek(SeekFrom::Start(offset))?;
+            if let Some(control_bytes) = control_bytes {
+                // The control-byte array precedes the slot data so a lookup
+                // can load a group's tags with a single read.
+                self.writer.write_all(&control_bytes)?;
+            }
             for (hash_val, data_offset) in slots_data {
                 // Write two u64 values directly
                 self.writer.write_all(&hash_val.to_le_bytes())?;
                 self.writer.write_all(&data_offset.to_le_bytes())?;
             }
-            // Each slot is (u64, u64), so 16 bytes per slot. num_slots is the count of such slots.
-            current_pos_for_hash_tables += (num_slots as u64) * 16;
+        }
+
+        // Append the optional Bloom filter region after the hash tables.
+        let mut trailer = Trailer {
+            codec: self.codec,
+            swiss_table: self.swiss_table,
+            bloom_offset: 0,
+            bloom_nbits: 0,
+            bloom_k: 0,
+        };
+        if let Some(bits_per_key) = self.bloom_bits_per_key {
+            let (bits, nbits, k) = bloom::build(&self.bloom_hashes, bits_per_key);
+            self.writer
+                .seek(SeekFrom::Start(current_pos_for_hash_tables))?;
+            self.writer.write_all(&bits)?;
+            trailer.bloom_offset = current_pos_for_hash_tables;
+            trailer.bloom_nbits = nbits;
+            trailer.bloom_k = k;
+            current_pos_for_hash_tables += bits.len() as u64;
+        }
+
+        // Append the trailer so the reader can discover the codec and filter.
+        // A plain, filter-less, uncompressed file gets no trailer and stays
+        // byte-for-byte identical to the classic layout.
+        if trailer.is_needed() {
+            self.writer
+                .seek(SeekFrom::Start(current_pos_for_hash_tables))?;
+            self.writer.write_all(&trailer.to_bytes())?;
         }
 
         self.writer.seek(SeekFrom::Start(0))?;
@@ -177,6 +934,7 @@ impl<W: Write + Seek, H: Hasher + Default> CdbWriter<W, H> {
 
     pub fn finalize(&mut self) -> Result<(), Error> {
         self.write_footer_and_header()?;
+        self.write_format_trailer(false)?;
         self.writer.flush()?;
         Ok(())
     }
@@ -261,6 +1019,131 @@ impl<H: Hasher + Default> CdbWriter<File, H> {
         self.write_footer_and_header()?;
         self.writer.flush()?;
 
-        Cdb::open(path_to_reopen).map_err(Error::Io)
+        Cdb::open(path_to_reopen)
+    }
+
+    /// Finalizes the writer like [`finalize`](Self::finalize), then appends a
+    /// small trailer recording a checksum over everything after the
+    /// 4096-byte header — the data section, the hash tables, and any
+    /// compression/Bloom trailer already written.
+    ///
+    /// [`Cdb::open_verified`](crate::Cdb::open_verified) recomputes the same
+    /// checksum and returns [`Error::ChecksumMismatch`] if it disagrees. The
+    /// header and the regular [`open`](crate::Cdb::open)/[`get`](crate::Cdb::get)
+    /// path are untouched, so databases written without this trailer keep
+    /// opening normally.
+    pub fn finalize_with_checksum(&mut self) -> Result<(), Error> {
+        self.write_footer_and_header()?;
+        self.writer.flush()?;
+
+        let file_len = self.writer.metadata()?.len();
+        let body_len = file_len - HEADER_SIZE;
+        let checksum = checksum::checksum_body(&self.writer, file_len)?;
+
+        self.writer.seek(SeekFrom::Start(file_len))?;
+        self.writer.write_all(&checksum::CHECKSUM_TRAILER_MAGIC)?;
+        self.writer.write_all(&checksum.to_le_bytes())?;
+        self.writer.write_all(&body_len.to_le_bytes())?;
+
+        self.write_format_trailer(true)?;
+        self.writer.flush()?;
+
+        Ok(())
+    }
+}
+
+impl<H: Hasher + Default> CdbWriter<crate::split::SplitWriter, H> {
+    /// Creates a multi-volume CDB whose data spans `<prefix>.000`,
+    /// `<prefix>.001`, ... parts of at most `max_bytes_per_file` bytes each,
+    /// for databases too large for a single filesystem's file-size limit.
+    ///
+    /// Offsets recorded in the header and hash tables are global across all
+    /// parts, exactly like a single-file database; only the underlying
+    /// [`Write`]/[`Seek`] sink ([`SplitWriter`](crate::split::SplitWriter))
+    /// knows they're really spread across several files. Read it back with
+    /// [`Cdb::open_split`](crate::Cdb::open_split), passing the same
+    /// `max_bytes_per_file`.
+    pub fn create_split(
+        prefix: impl AsRef<Path>,
+        max_bytes_per_file: u64,
+    ) -> Result<Self, Error> {
+        let writer = crate::split::SplitWriter::create(prefix, max_bytes_per_file)?;
+        Self::new(writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An empty table gets no slot array at all, not a zero-length one.
+    #[test]
+    fn build_table_slots_empty_table() {
+        let (control_bytes, slots) = build_table_slots(&[], 0, false);
+        assert!(control_bytes.is_none());
+        assert!(slots.is_empty());
+    }
+
+    /// Each entry lands at `(hash >> 8) % num_slots` when that slot is free.
+    #[test]
+    fn build_table_slots_places_entries_at_their_home_slot() {
+        let entries = vec![
+            Entry {
+                hash_val: 0x00,
+                offset: 100,
+            },
+            Entry {
+                hash_val: 0x200,
+                offset: 200,
+            },
+        ];
+        let (_, slots) = build_table_slots(&entries, 4, false);
+        assert_eq!(slots[0], (0x00, 100));
+        assert_eq!(slots[2], (0x200, 200));
+        assert_eq!(slots[1], (0, 0));
+        assert_eq!(slots[3], (0, 0));
+    }
+
+    /// Two entries whose home slot collides both land in the table via
+    /// linear probing, in insertion order, rather than one overwriting the
+    /// other.
+    #[test]
+    fn build_table_slots_linear_probes_past_collisions() {
+        // All three hashes share a home slot of 0 in a 4-slot table
+        // ((hash >> 8) % 4 == 0 for each), so the 2nd and 3rd must probe
+        // forward to slots 1 and 2 instead of overwriting the 1st.
+        let entries = vec![
+            Entry {
+                hash_val: 0x000,
+                offset: 10,
+            },
+            Entry {
+                hash_val: 0x400,
+                offset: 20,
+            },
+            Entry {
+                hash_val: 0x800,
+                offset: 30,
+            },
+        ];
+        let (_, slots) = build_table_slots(&entries, 4, false);
+        assert_eq!(slots[0], (0x000, 10));
+        assert_eq!(slots[1], (0x400, 20));
+        assert_eq!(slots[2], (0x800, 30));
+        assert_eq!(slots[3], (0, 0));
+    }
+
+    /// With `swiss_table` set, each occupied slot's control byte is the
+    /// low 7 bits of its hash, and empty slots stay `control::EMPTY`.
+    #[test]
+    fn build_table_slots_fills_control_bytes_when_swiss_table() {
+        let entries = vec![Entry {
+            hash_val: 0xab,
+            offset: 1,
+        }];
+        let (control_bytes, _) = build_table_slots(&entries, 2, true);
+        let control_bytes = control_bytes.expect("swiss_table should produce control bytes");
+        assert_eq!(control_bytes[0], control::tag(0xab));
+        assert_eq!(control_bytes[1], control::EMPTY);
     }
 }
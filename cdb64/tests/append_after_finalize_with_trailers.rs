@@ -0,0 +1,39 @@
+use cdb64::{Cdb, CdbHash, CdbWriter, Error};
+
+/// Regression test for `CdbWriter::from_existing` mis-locating the
+/// compression/layout trailer when a checksum and/or format-stamp trailer is
+/// stacked on top of it at EOF.
+///
+/// `from_existing` used to assume that trailer was unconditionally the last
+/// `TRAILER_LEN` bytes of the file; with a checksum trailer present that read
+/// landed inside the checksum trailer instead, silently failed to parse as a
+/// compression trailer, and fell back to `Codec::Stored`/no SwissTable --
+/// losing track of the database's actual on-disk layout before appending
+/// more records.
+#[test]
+fn test_append_after_finalize_with_checksum_and_format_stamp() -> Result<(), Error> {
+    let temp_file = tempfile::NamedTempFile::new().expect("Failed to create temporary file");
+    let file_path = temp_file.path();
+
+    let mut writer = CdbWriter::<_, CdbHash>::create(file_path)?
+        .with_swiss_table()
+        .with_format_stamp(0);
+    writer.put(b"old-key-1", b"old-value-1")?;
+    writer.put(b"old-key-2", b"old-value-2")?;
+    writer.finalize_with_checksum()?;
+
+    // Reopening for append must peel the format-stamp trailer, then the
+    // checksum trailer, before it can find the compression/layout trailer
+    // underneath both of them.
+    let mut writer = CdbWriter::<_, CdbHash>::open_append(file_path)?;
+    writer.put(b"new-key-1", b"new-value-1")?;
+    writer.finalize_with_checksum()?;
+
+    let cdb = Cdb::<_, CdbHash>::open_verified(file_path)?;
+    assert_eq!(cdb.get(b"old-key-1")?.unwrap(), b"old-value-1");
+    assert_eq!(cdb.get(b"old-key-2")?.unwrap(), b"old-value-2");
+    assert_eq!(cdb.get(b"new-key-1")?.unwrap(), b"new-value-1");
+    assert_eq!(cdb.iter().count(), 3);
+
+    Ok(())
+}
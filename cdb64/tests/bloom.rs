@@ -0,0 +1,50 @@
+use cdb64::{Cdb, CdbHash, CdbWriter, Error};
+use std::io::Cursor;
+
+/// A database built `with_bloom` still answers `get` correctly for both
+/// present and absent keys -- the filter only changes how fast a miss is
+/// rejected, never the result.
+#[test]
+fn test_bloom_round_trip() -> Result<(), Error> {
+    let mut writer = CdbWriter::<_, CdbHash>::new(Cursor::new(Vec::new()))?.with_bloom(10);
+
+    for i in 0..200 {
+        let key = format!("key_{i}");
+        let value = format!("value_{i}");
+        writer.put(key.as_bytes(), value.as_bytes())?;
+    }
+    writer.finalize()?;
+
+    let cursor = writer.into_inner()?;
+    let cdb = Cdb::<_, CdbHash>::new(cursor)?;
+
+    for i in 0..200 {
+        let key = format!("key_{i}");
+        let expected = format!("value_{i}");
+        let value = cdb
+            .get(key.as_bytes())?
+            .unwrap_or_else(|| panic!("key {key} should exist"));
+        assert_eq!(value, expected.as_bytes());
+    }
+
+    assert!(cdb.get(b"definitely-not-in-the-filter")?.is_none());
+
+    Ok(())
+}
+
+/// [`CdbWriter::with_default_bloom`] behaves the same as an explicit
+/// `with_bloom` call, just at the crate's default bits-per-key.
+#[test]
+fn test_default_bloom_round_trip() -> Result<(), Error> {
+    let mut writer = CdbWriter::<_, CdbHash>::new(Cursor::new(Vec::new()))?.with_default_bloom();
+    writer.put(b"hello", b"world")?;
+    writer.finalize()?;
+
+    let cursor = writer.into_inner()?;
+    let cdb = Cdb::<_, CdbHash>::new(cursor)?;
+
+    assert_eq!(cdb.get(b"hello")?.unwrap(), b"world");
+    assert!(cdb.get(b"missing")?.is_none());
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use cdb64::{Cdb, CdbHash, CdbWriter, Error};
+use tempfile::NamedTempFile;
+
+/// A database written with `finalize_with_checksum` opens and reads back
+/// correctly through `open_verified`.
+#[test]
+fn test_checksum_round_trip() -> Result<(), Error> {
+    let temp_file = NamedTempFile::new().expect("Failed to create temporary file");
+    let file_path = temp_file.path();
+
+    let mut writer = CdbWriter::<_, CdbHash>::create(file_path)?;
+    writer.put(b"hello", b"world")?;
+    writer.put(b"rust", b"is awesome")?;
+    writer.finalize_with_checksum()?;
+
+    let cdb = Cdb::<_, CdbHash>::open_verified(file_path)?;
+    assert_eq!(cdb.get(b"hello")?.unwrap(), b"world");
+    assert_eq!(cdb.get(b"rust")?.unwrap(), b"is awesome");
+
+    Ok(())
+}
+
+/// A checksummed file still opens normally through the plain, unverified
+/// `open` path -- the trailer is purely additive.
+#[test]
+fn test_checksum_trailer_ignored_by_plain_open() -> Result<(), Error> {
+    let temp_file = NamedTempFile::new().expect("Failed to create temporary file");
+    let file_path = temp_file.path();
+
+    let mut writer = CdbWriter::<_, CdbHash>::create(file_path)?;
+    writer.put(b"key", b"value")?;
+    writer.finalize_with_checksum()?;
+
+    let cdb = Cdb::<_, CdbHash>::open(file_path)?;
+    assert_eq!(cdb.get(b"key")?.unwrap(), b"value");
+
+    Ok(())
+}
+
+/// Corrupting a byte in the data section after `finalize_with_checksum`
+/// makes `open_verified` fail with `ChecksumMismatch`.
+#[test]
+fn test_open_verified_detects_corruption() -> Result<(), Error> {
+    let temp_file = NamedTempFile::new().expect("Failed to create temporary file");
+    let file_path = temp_file.path();
+
+    let mut writer = CdbWriter::<_, CdbHash>::create(file_path)?;
+    writer.put(b"key", b"value")?;
+    writer.finalize_with_checksum()?;
+
+    // Flip the first byte of the data section (right after the fixed
+    // 4096-byte header), which the checksum covers but the header doesn't.
+    let mut bytes = std::fs::read(file_path).expect("read back the file");
+    let corrupt_at = 4096;
+    bytes[corrupt_at] ^= 0xff;
+    std::fs::write(file_path, &bytes).expect("write corrupted bytes back");
+
+    let result = Cdb::<_, CdbHash>::open_verified(file_path);
+    assert!(matches!(result, Err(Error::ChecksumMismatch)));
+
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use cdb64::{Cdb, CdbHash, ClassicCdb, Error, djb_hash32};
+use std::io::Cursor;
+
+/// Hand-builds a minimal classic 32-bit cdb file's bytes for `records`,
+/// following the same `(key_len, val_len)`-prefixed record layout and
+/// linear-probed `(hash, pos)` slot tables [`ClassicCdb`] reads.
+///
+/// There is no classic-format writer in this crate (only [`ClassicCdb`],
+/// which reads), so this is the only way to exercise it without a fixture
+/// file checked into the repo.
+fn build_classic_cdb(records: &[(&[u8], &[u8])]) -> Vec<u8> {
+    const HEADER_SIZE_CLASSIC: usize = 256 * 4 * 2;
+
+    let mut data = vec![0u8; HEADER_SIZE_CLASSIC];
+    let mut buckets: Vec<Vec<(u32, u32)>> = vec![Vec::new(); 256];
+
+    for (key, value) in records {
+        let pos = data.len() as u32;
+        data.extend_from_slice(&(key.len() as u32).to_le_bytes());
+        data.extend_from_slice(&(value.len() as u32).to_le_bytes());
+        data.extend_from_slice(key);
+        data.extend_from_slice(value);
+
+        let hash = djb_hash32(key);
+        buckets[(hash & 0xff) as usize].push((hash, pos));
+    }
+
+    let mut header = vec![0u8; HEADER_SIZE_CLASSIC];
+    for (table_idx, entries) in buckets.iter().enumerate() {
+        if entries.is_empty() {
+            continue;
+        }
+        let nslots = (entries.len() * 2) as u32;
+        let mut slots = vec![(0u32, 0u32); nslots as usize];
+        for &(hash, pos) in entries {
+            let mut slot = (hash >> 8) % nslots;
+            while slots[slot as usize] != (0, 0) {
+                slot = (slot + 1) % nslots;
+            }
+            slots[slot as usize] = (hash, pos);
+        }
+
+        let table_pos = data.len() as u32;
+        for (hash, pos) in slots {
+            data.extend_from_slice(&hash.to_le_bytes());
+            data.extend_from_slice(&pos.to_le_bytes());
+        }
+
+        header[table_idx * 8..table_idx * 8 + 4].copy_from_slice(&table_pos.to_le_bytes());
+        header[table_idx * 8 + 4..table_idx * 8 + 8].copy_from_slice(&nslots.to_le_bytes());
+    }
+
+    data[..HEADER_SIZE_CLASSIC].copy_from_slice(&header);
+    data
+}
+
+/// `ClassicCdb` reads back records written in the classic 32-bit layout,
+/// both via `get` and via a sequential `iter`.
+#[test]
+fn test_classic_cdb_round_trip() -> Result<(), Error> {
+    let records: Vec<(&[u8], &[u8])> = vec![
+        (b"foo", b"bar"),
+        (b"baz", b"quuuux"),
+        (b"crystal", b"CASTLES"),
+        (b"", b"empty_key"),
+        (b"empty_value", b""),
+    ];
+    let bytes = build_classic_cdb(&records);
+
+    let classic = ClassicCdb::new(Cursor::new(bytes))?;
+    for (key, value) in &records {
+        assert_eq!(classic.get(key)?.as_deref(), Some(*value), "key={key:?}");
+    }
+    assert!(classic.get(b"not in the table")?.is_none());
+
+    let iterated: Vec<_> = classic.iter().collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(iterated.len(), records.len());
+    for (key, value) in &records {
+        assert!(
+            iterated
+                .iter()
+                .any(|(k, v)| k.as_slice() == *key && v.as_slice() == *value)
+        );
+    }
+
+    Ok(())
+}
+
+/// `upgrade_classic` migrates a classic-format file into a native 64-bit one
+/// that `Cdb::open` can then read.
+#[test]
+fn test_upgrade_classic_round_trip() -> Result<(), Error> {
+    let records: Vec<(&[u8], &[u8])> = vec![(b"a", b"1"), (b"b", b"2"), (b"c", b"3")];
+    let bytes = build_classic_cdb(&records);
+
+    let src_file = tempfile::NamedTempFile::new().expect("Failed to create temporary file");
+    std::fs::write(src_file.path(), &bytes).expect("write classic cdb bytes");
+    let new_file = tempfile::NamedTempFile::new().expect("Failed to create temporary file");
+
+    cdb64::upgrade_classic::<CdbHash>(src_file.path(), new_file.path())?;
+
+    let cdb = Cdb::<_, CdbHash>::open(new_file.path())?;
+    for (key, value) in &records {
+        assert_eq!(cdb.get(key)?.as_deref(), Some(*value));
+    }
+
+    Ok(())
+}
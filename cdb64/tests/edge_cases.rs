@@ -2,6 +2,32 @@ use cdb64::{Cdb, CdbHash, CdbWriter, Error};
 use std::io::Cursor;
 use tempfile::NamedTempFile;
 
+/// Regression test: a file-backed writer with several *distinct* keys must
+/// reopen with every one of them readable, not just the first.
+///
+/// `CdbWriter::put` advances `current_data_offset` by the on-disk record
+/// stride on every call; if that stride is wrong, only the first record's
+/// offset (matching where the writer actually started) stays correct --
+/// every later record's hash-table entry points past where it truly landed.
+#[test]
+fn test_multiple_distinct_keys_round_trip() -> Result<(), Error> {
+    let temp_file = NamedTempFile::new().expect("Failed to create temporary file");
+    let file_path = temp_file.path();
+
+    let mut writer = CdbWriter::<_, CdbHash>::create(file_path)?;
+    writer.put(b"key1", b"value1")?;
+    writer.put(b"key2", b"value2")?;
+    writer.put(b"key3", b"value3")?;
+    writer.finalize()?;
+
+    let cdb = Cdb::<_, CdbHash>::open(file_path)?;
+    assert_eq!(cdb.get(b"key1")?.as_deref(), Some(b"value1".as_ref()));
+    assert_eq!(cdb.get(b"key2")?.as_deref(), Some(b"value2".as_ref()));
+    assert_eq!(cdb.get(b"key3")?.as_deref(), Some(b"value3".as_ref()));
+
+    Ok(())
+}
+
 /// Test handling of duplicate keys.
 /// CDB allows duplicate keys - all are stored but get() returns the first match.
 #[test]
@@ -214,6 +240,40 @@ fn test_into_inner_without_finalize() {
     }
 }
 
+/// Round-trip `iter()` over many records of varying key/value lengths.
+///
+/// Regression test for `CdbIterator::next` assuming a 16-byte record header
+/// instead of the real 8-byte `(key_len: u32, val_len: u32)` one: with the
+/// wrong stride, every record after the first is misaligned, surfacing as
+/// garbage bytes or an `Error::InvalidData("Record extends beyond expected
+/// data end")`.
+#[test]
+fn test_iterate_many_records_round_trip() -> Result<(), Error> {
+    let mut writer = CdbWriter::<_, CdbHash>::new(Cursor::new(Vec::new()))?;
+
+    let mut expected = Vec::new();
+    for i in 0..200 {
+        let key = format!("iter_key_{i}").into_bytes();
+        let value = vec![b'v'; i % 37]; // varying value length, including 0
+        writer.put(&key, &value)?;
+        expected.push((key, value));
+    }
+    writer.finalize()?;
+
+    let cursor = writer.into_inner()?;
+    let cdb = Cdb::<_, CdbHash>::new(cursor)?;
+
+    let iterated: Vec<_> = cdb.iter().collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(iterated, expected);
+
+    // Independently, every key must also resolve through get().
+    for (key, value) in &expected {
+        assert_eq!(cdb.get(key)?.as_ref(), Some(value));
+    }
+
+    Ok(())
+}
+
 /// Test that all 256 hash tables can be used.
 #[test]
 fn test_all_hash_tables_coverage() -> Result<(), Error> {
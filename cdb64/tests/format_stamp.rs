@@ -0,0 +1,65 @@
+use cdb64::{Cdb, CdbHash, CdbWriter, Error};
+use std::io::Cursor;
+
+/// A database written `with_format_stamp` opens and reads back normally --
+/// the stamp is purely additive to the classic layout.
+#[test]
+fn test_format_stamp_round_trip() -> Result<(), Error> {
+    let mut writer = CdbWriter::<_, CdbHash>::new(Cursor::new(Vec::new()))?.with_format_stamp(0);
+    writer.put(b"hello", b"world")?;
+    writer.finalize()?;
+
+    let data = writer.into_inner()?.into_inner();
+    let cdb = Cdb::<_, CdbHash>::new(Cursor::new(data))?;
+    assert_eq!(cdb.get(b"hello")?.unwrap(), b"world");
+
+    Ok(())
+}
+
+/// `upgrade` re-stamps a classic, unstamped database as the current format
+/// version without losing any records.
+#[test]
+fn test_upgrade_adds_format_stamp() -> Result<(), Error> {
+    let old_file = tempfile::NamedTempFile::new().expect("Failed to create temporary file");
+    let new_file = tempfile::NamedTempFile::new().expect("Failed to create temporary file");
+
+    let mut writer = CdbWriter::<_, CdbHash>::create(old_file.path())?;
+    writer.put(b"a", b"1")?;
+    writer.put(b"b", b"2")?;
+    writer.finalize()?;
+
+    cdb64::upgrade::<CdbHash>(old_file.path(), new_file.path())?;
+
+    let cdb = Cdb::<_, CdbHash>::open(new_file.path())?;
+    assert_eq!(cdb.get(b"a")?.unwrap(), b"1");
+    assert_eq!(cdb.get(b"b")?.unwrap(), b"2");
+
+    Ok(())
+}
+
+/// A format stamp naming a version newer than this build understands is
+/// rejected by `Cdb::open` rather than silently mis-parsed.
+#[test]
+fn test_open_rejects_unsupported_format_version() -> Result<(), Error> {
+    let temp_file = tempfile::NamedTempFile::new().expect("Failed to create temporary file");
+    let file_path = temp_file.path();
+
+    let mut writer = CdbWriter::<_, CdbHash>::create(file_path)?.with_format_stamp(0);
+    writer.put(b"key", b"value")?;
+    writer.finalize()?;
+
+    let mut data = std::fs::read(file_path).expect("read back the file");
+
+    // The format stamp trailer is the last 16 bytes: an 8-byte "CDB64FMT"
+    // magic, then a little-endian u16 version at offset 8..10 within it.
+    let trailer_start = data.len() - 16;
+    assert_eq!(&data[trailer_start..trailer_start + 8], b"CDB64FMT");
+    let version_at = trailer_start + 8;
+    data[version_at..version_at + 2].copy_from_slice(&u16::MAX.to_le_bytes());
+    std::fs::write(file_path, &data).expect("write patched bytes back");
+
+    let result = Cdb::<_, CdbHash>::open(file_path);
+    assert!(matches!(result, Err(Error::UnsupportedFormat(v)) if v == u16::MAX));
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use cdb64::{Cdb, CdbHash, CdbWriter, Error};
+use std::io::Cursor;
+
+/// `finalize` builds all 256 hash tables' slot arrays from `write_footer_and_header`,
+/// which runs across a thread pool when the `rayon` feature is enabled and
+/// sequentially otherwise. Both paths call the same per-table
+/// `build_table_slots` logic, so this round-trip over enough entries to
+/// populate every table exercises whichever one this build was compiled
+/// with.
+#[test]
+fn test_finalize_round_trip_across_all_tables() -> Result<(), Error> {
+    let mut writer = CdbWriter::<_, CdbHash>::new(Cursor::new(Vec::new()))?;
+
+    for i in 0..5_000 {
+        let key = format!("finalize_key_{i:05}");
+        let value = format!("finalize_value_{i:05}");
+        writer.put(key.as_bytes(), value.as_bytes())?;
+    }
+    writer.finalize()?;
+
+    let cursor = writer.into_inner()?;
+    let cdb = Cdb::<_, CdbHash>::new(cursor)?;
+
+    for i in 0..5_000 {
+        let key = format!("finalize_key_{i:05}");
+        let expected = format!("finalize_value_{i:05}");
+        let value = cdb
+            .get(key.as_bytes())?
+            .unwrap_or_else(|| panic!("key {key} should exist"));
+        assert_eq!(value, expected.as_bytes());
+    }
+
+    assert_eq!(cdb.iter().count(), 5_000);
+
+    Ok(())
+}
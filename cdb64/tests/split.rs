@@ -0,0 +1,41 @@
+#![cfg(unix)]
+
+use cdb64::{Cdb, CdbHash, CdbWriter, Error};
+
+/// A database written across several `<prefix>.NNN` parts via `create_split`
+/// reads back correctly through `open_split`, including records that land on
+/// either side of a part boundary.
+#[test]
+fn test_split_round_trip() -> Result<(), Error> {
+    let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+    let prefix = dir.path().join("db");
+
+    // Small enough that 200 records definitely span several parts.
+    let max_bytes_per_file = 4096 * 2;
+
+    let mut writer = CdbWriter::<_, CdbHash>::create_split(&prefix, max_bytes_per_file)?;
+    for i in 0..200 {
+        let key = format!("split_key_{i:04}");
+        let value = format!("split_value_{i:04}");
+        writer.put(key.as_bytes(), value.as_bytes())?;
+    }
+    writer.finalize()?;
+
+    assert!(prefix.with_extension("000").exists());
+    assert!(
+        prefix.with_extension("001").exists(),
+        "200 records should span more than one part at this part size"
+    );
+
+    let cdb = Cdb::<_, CdbHash>::open_split(&prefix, max_bytes_per_file)?;
+    for i in 0..200 {
+        let key = format!("split_key_{i:04}");
+        let expected = format!("split_value_{i:04}");
+        let value = cdb
+            .get(key.as_bytes())?
+            .unwrap_or_else(|| panic!("key {key} should exist"));
+        assert_eq!(value, expected.as_bytes());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use cdb64::{Cdb, CdbHash, CdbWriter, Error};
+use std::io::Cursor;
+
+/// A database built `with_swiss_table` round-trips the same as the classic
+/// bare slot array, including dense collision chains that exercise the
+/// control-byte groups' probing past more than one group.
+#[test]
+fn test_swiss_table_round_trip() -> Result<(), Error> {
+    let mut writer = CdbWriter::<_, CdbHash>::new(Cursor::new(Vec::new()))?.with_swiss_table();
+
+    for i in 0..5_000 {
+        let key = format!("swiss_key_{i}");
+        let value = format!("swiss_value_{i}");
+        writer.put(key.as_bytes(), value.as_bytes())?;
+    }
+    writer.finalize()?;
+
+    let cursor = writer.into_inner()?;
+    let cdb = Cdb::<_, CdbHash>::new(cursor)?;
+
+    for i in 0..5_000 {
+        let key = format!("swiss_key_{i}");
+        let expected = format!("swiss_value_{i}");
+        let value = cdb
+            .get(key.as_bytes())?
+            .unwrap_or_else(|| panic!("key {key} should exist"));
+        assert_eq!(value, expected.as_bytes());
+    }
+
+    assert!(cdb.get(b"not-in-the-table")?.is_none());
+
+    let count = cdb.iter().count();
+    assert_eq!(count, 5_000);
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use cdb64::{Cdb, CdbHash, CdbWriter, Error};
+use std::io::Cursor;
+
+/// Records written with `load_text` round-trip back out through `dump_text`.
+#[test]
+fn test_text_load_dump_round_trip() -> Result<(), Error> {
+    let input = b"+3,5:one->hello\n+3,5:two->world\n\n";
+
+    let mut writer = CdbWriter::<_, CdbHash>::new(Cursor::new(Vec::new()))?;
+    writer.load_text(&input[..])?;
+    writer.finalize()?;
+    let data = writer.into_inner()?.into_inner();
+
+    let cdb = Cdb::<_, CdbHash>::new(Cursor::new(data))?;
+    assert_eq!(cdb.get(b"one")?.unwrap(), b"hello");
+    assert_eq!(cdb.get(b"two")?.unwrap(), b"world");
+
+    let mut dumped = Vec::new();
+    cdb.dump_text(&mut dumped)?;
+
+    // Compare the set of record lines, independent of their order.
+    let mut got: Vec<&[u8]> = dumped.split(|&b| b == b'\n').collect();
+    let mut want: Vec<&[u8]> = input.split(|&b| b == b'\n').collect();
+    got.sort_unstable();
+    want.sort_unstable();
+    assert_eq!(got, want);
+
+    Ok(())
+}
+
+/// Keys and values containing newlines survive the length-prefixed parse.
+#[test]
+fn test_text_load_binary_payload() -> Result<(), Error> {
+    let input = b"+2,3:a\n->x\ny\n\n";
+
+    let mut writer = CdbWriter::<_, CdbHash>::new(Cursor::new(Vec::new()))?;
+    writer.load_text(&input[..])?;
+    writer.finalize()?;
+    let data = writer.into_inner()?.into_inner();
+
+    let cdb = Cdb::<_, CdbHash>::new(Cursor::new(data))?;
+    assert_eq!(cdb.get(b"a\n")?.unwrap(), b"x\ny");
+
+    Ok(())
+}
+
+/// A record whose key and data are not separated by `->` is rejected.
+#[test]
+fn test_text_load_rejects_malformed() {
+    let input = b"+3,5:one=>hello\n\n"; // '=>' instead of '->'
+
+    let mut writer = CdbWriter::<_, CdbHash>::new(Cursor::new(Vec::new())).unwrap();
+    let result = writer.load_text(&input[..]);
+    assert!(matches!(result, Err(Error::InvalidData(_))));
+}
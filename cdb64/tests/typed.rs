@@ -0,0 +1,46 @@
+#![cfg(feature = "serde")]
+
+use cdb64::{Cdb, CdbHash, CdbWriter, Error, TypedCdb};
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Record {
+    id: u32,
+    name: String,
+    tags: Vec<String>,
+}
+
+/// A value written with `put_typed` (the default `CborCodec`) round-trips
+/// back out through `TypedCdb::get_typed`.
+#[test]
+fn test_typed_cbor_round_trip() -> Result<(), Error> {
+    let mut writer = CdbWriter::<_, CdbHash>::new(Cursor::new(Vec::new()))?;
+    writer.put_typed::<_, cdb64::CborCodec>(
+        b"rec-1",
+        &Record {
+            id: 1,
+            name: "first".into(),
+            tags: vec!["a".into(), "b".into()],
+        },
+    )?;
+    writer.finalize()?;
+
+    let cursor = writer.into_inner()?;
+    let cdb = Cdb::<_, CdbHash>::new(cursor)?;
+    let typed: TypedCdb<_, CdbHash, cdb64::CborCodec> = TypedCdb::new(cdb);
+
+    let got: Record = typed.get_typed(b"rec-1")?.expect("record should exist");
+    assert_eq!(
+        got,
+        Record {
+            id: 1,
+            name: "first".into(),
+            tags: vec!["a".into(), "b".into()],
+        }
+    );
+    let missing: Option<Record> = typed.get_typed(b"missing")?;
+    assert!(missing.is_none());
+
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use cdb64::{Cdb, CdbHash, CdbWriter, Error, WriteBatch};
+use std::io::Cursor;
+
+/// Records queued on a `WriteBatch` and committed via `write_batch` round-trip
+/// exactly like records put directly.
+#[test]
+fn test_write_batch_round_trip() -> Result<(), Error> {
+    let mut batch = WriteBatch::new();
+    batch.put(b"one", b"1");
+    batch.put(b"two", b"2");
+    batch.put(b"three", b"3");
+    assert_eq!(batch.len(), 3);
+    assert!(!batch.is_empty());
+
+    let mut writer = CdbWriter::<_, CdbHash>::new(Cursor::new(Vec::new()))?;
+    writer
+        .write_batch(batch)
+        .map_err(|(_, err)| err)
+        .expect("batch should commit cleanly");
+    writer.finalize()?;
+
+    let cursor = writer.into_inner()?;
+    let cdb = Cdb::<_, CdbHash>::new(cursor)?;
+
+    assert_eq!(cdb.get(b"one")?.unwrap(), b"1");
+    assert_eq!(cdb.get(b"two")?.unwrap(), b"2");
+    assert_eq!(cdb.get(b"three")?.unwrap(), b"3");
+
+    Ok(())
+}
+
+/// Several batches committed in sequence each keep their own relative
+/// insertion order within a shared table, per `write_batch`'s stable sort.
+#[test]
+fn test_multiple_batches_preserve_relative_order() -> Result<(), Error> {
+    let mut writer = CdbWriter::<_, CdbHash>::new(Cursor::new(Vec::new()))?;
+
+    let mut first = WriteBatch::new();
+    first.put(b"dup", b"from-first-batch");
+    writer.write_batch(first).map_err(|(_, err)| err)?;
+
+    let mut second = WriteBatch::new();
+    second.put(b"dup", b"from-second-batch");
+    writer.write_batch(second).map_err(|(_, err)| err)?;
+
+    writer.finalize()?;
+
+    let cursor = writer.into_inner()?;
+    let cdb = Cdb::<_, CdbHash>::new(cursor)?;
+
+    let dups: Vec<_> = cdb
+        .iter()
+        .filter_map(|r| r.ok())
+        .filter(|(k, _)| k == b"dup")
+        .collect();
+    assert_eq!(dups.len(), 2);
+    assert_eq!(dups[0].1, b"from-first-batch");
+    assert_eq!(dups[1].1, b"from-second-batch");
+
+    Ok(())
+}
@@ -39,6 +39,7 @@ impl CdbWriter {
 #[napi]
 pub struct Cdb {
   inner: cdb64::Cdb<File, CdbHash>,
+  path: String,
 }
 
 #[napi]
@@ -46,7 +47,7 @@ impl Cdb {
   #[napi(factory)]
   pub fn open(path: String) -> napi::Result<Self> {
     let cdb = cdb64::Cdb::<_, CdbHash>::open(&path).map_err(|e| js_err(e.into()))?;
-    Ok(Cdb { inner: cdb })
+    Ok(Cdb { inner: cdb, path })
   }
 
   #[napi]
@@ -67,6 +68,59 @@ impl Cdb {
     }
     Ok(out)
   }
+
+  /// Returns a lazy iterator that yields one entry per `next()` call.
+  ///
+  /// Unlike `iter()`, this does not materialize every record up front, so peak
+  /// memory stays proportional to a single entry — suitable for databases far
+  /// larger than memory.
+  #[napi]
+  pub fn iterator(&self) -> napi::Result<CdbIterator> {
+    let cdb = cdb64::Cdb::<_, CdbHash>::open(&self.path).map_err(|e| js_err(e.into()))?;
+    Ok(CdbIterator::new(cdb))
+  }
+}
+
+/// A lazy, streaming iterator over a CDB database.
+///
+/// The iterator owns its own `Cdb` handle so it can outlive the borrow that the
+/// underlying `Cdb::iter()` requires; state is advanced on demand, one record at
+/// a time, by repeated calls to [`CdbIterator::next`].
+#[napi]
+pub struct CdbIterator {
+  // Owning the Cdb keeps it alive for as long as the borrowing iterator below.
+  cdb: cdb64::Cdb<File, CdbHash>,
+  inner: Option<cdb64::CdbIterator<'static, File, CdbHash>>,
+}
+
+#[napi]
+impl CdbIterator {
+  fn new(cdb: cdb64::Cdb<File, CdbHash>) -> Self {
+    CdbIterator { cdb, inner: None }
+  }
+
+  /// Advances the iterator, returning the next entry or `null` at the end.
+  #[napi]
+  pub fn next(&mut self) -> napi::Result<Option<CdbEntry>> {
+    if self.inner.is_none() {
+      // SAFETY: the borrow is tied to `self.cdb`, which this struct owns and
+      // keeps alive for at least as long as `inner`; the iterator never escapes
+      // this struct. This mirrors the owned-iterator pattern in the C binding.
+      let cdb_ref: &'static cdb64::Cdb<File, CdbHash> = unsafe { std::mem::transmute(&self.cdb) };
+      self.inner = Some(cdb_ref.iter());
+    }
+
+    match self.inner.as_mut().and_then(|it| it.next()) {
+      Some(entry) => {
+        let (k, v) = entry.map_err(|e| js_err(e.into()))?;
+        Ok(Some(CdbEntry {
+          key: Buffer::from(k),
+          value: Buffer::from(v),
+        }))
+      }
+      None => Ok(None),
+    }
+  }
 }
 
 fn js_err(e: CdbError) -> napi::Error {